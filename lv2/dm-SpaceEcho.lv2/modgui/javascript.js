@@ -1,21 +1,44 @@
 function(event) {
+  const time_state = event.data.time_state || (event.data.time_state = {
+    time_link: true,
+    sync_left: false,
+    sync_right: false,
+  });
+
+  function show(element, is_visible) {
+    if(is_visible) {
+      element.removeClass("hide");
+    } else {
+      element.addClass("hide");
+    }
+  }
+
+  function update_time_knobs() {
+    const { time_link, sync_left, sync_right } = time_state;
+    const knobs = (symbol) => event.icon.find(".mod-knob-image[mod-port-symbol=" + symbol + "]");
+
+    show(knobs("time_left").not(".linked-time-knob"), !sync_left);
+    show(knobs("division_left").not(".linked-division-knob"), sync_left);
+    show(knobs("time_right"), !time_link && !sync_right);
+    show(knobs("division_right"), !time_link && sync_right);
+    show(event.icon.find(".linked-time-knob"), time_link && !sync_left);
+    show(event.icon.find(".linked-division-knob"), time_link && sync_left);
+  }
+
   function handle_event(symbol, value) {
     
     switch (symbol) {
       case "time_link":
-            const time_link = event.icon.find("[mod-port-symbol=time_link]");
-            const time_right = event.icon.find("[mod-port-symbol=time_right]");
-            const linked_time_right = event.icon.find(".linked-time-knob");
-
+      case "sync_left":
+      case "sync_right":
+            const toggle = event.icon.find("[mod-port-symbol=" + symbol + "]");
             if(value == 1) {
-              time_link.addClass("on");
-              time_right.addClass("hide");
-              linked_time_right.removeClass("hide");
+              toggle.addClass("on");
             } else {
-              time_link.removeClass("on");
-              time_right.removeClass("hide");
-              linked_time_right.addClass("hide");
+              toggle.removeClass("on");
             }
+            time_state[symbol] = value == 1;
+            update_time_knobs();
             break;
         case "limiter":
             const limiter = event.icon.find("[mod-port-symbol=limiter]");
@@ -1,7 +1,14 @@
 extern crate lv2;
 extern crate space_echo;
+mod time_position;
 use lv2::prelude::*;
-use space_echo::{Params, SpaceEcho};
+use space_echo::{
+  sync::NoteDivision, ChannelMode, DuckDetector, DuckSource, DuckTarget, Interpolation, Meters,
+  Oversampling, ParamValues, Params, ReverbType, Routing, SaturationMode, SpaceEcho, TimeMode,
+  MAX_BLOCK_SIZE,
+};
+use std::sync::Arc;
+use time_position::{TimePosition, URIDs};
 
 #[derive(PortCollection)]
 struct Ports {
@@ -19,80 +26,370 @@ struct Ports {
   lowpass_freq: InputPort<InPlaceControl>,
   lowpass_res: InputPort<InPlaceControl>,
   reverb: InputPort<InPlaceControl>,
-  decay: InputPort<InPlaceControl>,
+  decay_time: InputPort<InPlaceControl>,
   stereo: InputPort<InPlaceControl>,
   duck: InputPort<InPlaceControl>,
   output: InputPort<InPlaceControl>,
   mix: InputPort<InPlaceControl>,
   limiter: InputPort<InPlaceControl>,
+  head_mode: InputPort<InPlaceControl>,
+  head_1_feedback: InputPort<InPlaceControl>,
+  head_2_feedback: InputPort<InPlaceControl>,
+  head_3_feedback: InputPort<InPlaceControl>,
+  sync_left: InputPort<InPlaceControl>,
+  sync_right: InputPort<InPlaceControl>,
+  division_left: InputPort<InPlaceControl>,
+  division_right: InputPort<InPlaceControl>,
+  saturation_mode: InputPort<InPlaceControl>,
+  drive: InputPort<InPlaceControl>,
+  width: InputPort<InPlaceControl>,
+  bias: InputPort<InPlaceControl>,
+  oversampling: InputPort<InPlaceControl>,
+  interpolation: InputPort<InPlaceControl>,
+  shimmer_interval: InputPort<InPlaceControl>,
+  shimmer: InputPort<InPlaceControl>,
+  reverb_type: InputPort<InPlaceControl>,
+  size: InputPort<InPlaceControl>,
+  predelay: InputPort<InPlaceControl>,
+  damping: InputPort<InPlaceControl>,
+  modulation_rate: InputPort<InPlaceControl>,
+  modulation_depth: InputPort<InPlaceControl>,
+  decay_low: InputPort<InPlaceControl>,
+  decay_high: InputPort<InPlaceControl>,
+  freeze: InputPort<InPlaceControl>,
+  routing: InputPort<InPlaceControl>,
+  duck_source: InputPort<InPlaceControl>,
+  duck_attack: InputPort<InPlaceControl>,
+  duck_release: InputPort<InPlaceControl>,
+  duck_hold: InputPort<InPlaceControl>,
+  duck_depth: InputPort<InPlaceControl>,
+  duck_detector: InputPort<InPlaceControl>,
+  duck_highpass_freq: InputPort<InPlaceControl>,
+  duck_target: InputPort<InPlaceControl>,
+  limiter_ceiling: InputPort<InPlaceControl>,
+  limiter_release: InputPort<InPlaceControl>,
+  limiter_link: InputPort<InPlaceControl>,
+  limiter_true_peak: InputPort<InPlaceControl>,
+  constant_latency: InputPort<InPlaceControl>,
+  latency: OutputPort<InPlaceControl>,
+  input_left_level: OutputPort<InPlaceControl>,
+  input_right_level: OutputPort<InPlaceControl>,
+  output_left_level: OutputPort<InPlaceControl>,
+  output_right_level: OutputPort<InPlaceControl>,
+  feedback_level: OutputPort<InPlaceControl>,
+  gain_compensation: OutputPort<InPlaceControl>,
+  duck_gain_reduction: OutputPort<InPlaceControl>,
+  limiter_gain_reduction: OutputPort<InPlaceControl>,
+  control: InputPort<AtomPort>,
   input_left: InputPort<InPlaceAudio>,
   input_right: InputPort<InPlaceAudio>,
   output_left: OutputPort<InPlaceAudio>,
   output_right: OutputPort<InPlaceAudio>,
+  sidechain_left: Option<InputPort<InPlaceAudio>>,
+  sidechain_right: Option<InputPort<InPlaceAudio>>,
+}
+
+#[derive(FeatureCollection)]
+pub struct Features<'a> {
+  map: LV2Map<'a>,
 }
 
 #[uri("https://github.com/davemollen/dm-SpaceEcho")]
 struct DmSpaceEcho {
   space_echo: SpaceEcho,
+  meters: Arc<Meters>,
   params: Params,
+  urids: URIDs,
+  time_position: TimePosition,
+}
+
+impl DmSpaceEcho {
+  fn get_time_params(&self, ports: &Ports) -> (f32, f32) {
+    let time_left = if ports.sync_left.get() == 1. {
+      self.get_synced_time(ports.division_left.get(), ports.time_left.get())
+    } else {
+      ports.time_left.get()
+    };
+
+    let time_right = match (ports.time_link.get() == 1., ports.sync_right.get() == 1.) {
+      (true, _) => time_left,
+      (false, true) => self.get_synced_time(ports.division_right.get(), ports.time_right.get()),
+      (false, false) => ports.time_right.get(),
+    };
+
+    (time_left, time_right)
+  }
+
+  /// Falls back to the free running time if the division port value is out of range.
+  fn get_synced_time(&self, division: f32, time: f32) -> f32 {
+    NoteDivision::try_from(division as i32 - 1)
+      .map(|division| division.to_ms(self.time_position.bpm))
+      .unwrap_or(time)
+  }
+
+  fn get_channel_mode(port_value: f32) -> ChannelMode {
+    match port_value {
+      2. => ChannelMode::PingPong,
+      _ => ChannelMode::Stereo,
+    }
+  }
+
+  fn get_time_mode(port_value: f32) -> TimeMode {
+    match port_value {
+      2. => TimeMode::Fade,
+      3. => TimeMode::Reverse,
+      _ => TimeMode::Repitch,
+    }
+  }
+
+  fn get_reverb_type(port_value: f32) -> ReverbType {
+    match port_value {
+      2. => ReverbType::Spring,
+      _ => ReverbType::Hall,
+    }
+  }
+
+  fn get_routing(port_value: f32) -> Routing {
+    match port_value {
+      2. => Routing::ReverbIntoEcho,
+      3. => Routing::Parallel,
+      4. => Routing::ReverbOnDryOnly,
+      _ => Routing::EchoIntoReverb,
+    }
+  }
+
+  fn get_duck_source(port_value: f32) -> DuckSource {
+    match port_value {
+      2. => DuckSource::Sidechain,
+      _ => DuckSource::Input,
+    }
+  }
+
+  fn get_duck_target(port_value: f32) -> DuckTarget {
+    match port_value {
+      2. => DuckTarget::Echo,
+      3. => DuckTarget::Reverb,
+      4. => DuckTarget::Feedback,
+      _ => DuckTarget::Wet,
+    }
+  }
+
+  fn get_duck_detector(port_value: f32) -> DuckDetector {
+    match port_value {
+      2. => DuckDetector::Rms,
+      _ => DuckDetector::Peak,
+    }
+  }
+
+  fn get_saturation_mode(port_value: f32) -> SaturationMode {
+    match port_value {
+      2. => SaturationMode::Hysteresis,
+      _ => SaturationMode::Cheap,
+    }
+  }
+
+  fn get_oversampling(port_value: f32) -> Oversampling {
+    match port_value {
+      2. => Oversampling::X2,
+      3. => Oversampling::X4,
+      _ => Oversampling::X1,
+    }
+  }
+
+  fn get_interpolation(port_value: f32) -> Interpolation {
+    match port_value {
+      1. => Interpolation::Step,
+      3. => Interpolation::Cosine,
+      4. => Interpolation::Cubic,
+      5. => Interpolation::Spline,
+      _ => Interpolation::Linear,
+    }
+  }
 }
 
 impl Plugin for DmSpaceEcho {
   // Tell the framework which ports this plugin has.
   type Ports = Ports;
 
-  // We don't need any special host features; We can leave them out.
-  type InitFeatures = ();
+  // The URID map is needed to read the time:Position atoms of the host.
+  type InitFeatures = Features<'static>;
   type AudioFeatures = ();
 
-  // Create a new instance of the plugin; Trivial in this case.
-  fn new(plugin_info: &PluginInfo, _features: &mut ()) -> Option<Self> {
+  // Create a new instance of the plugin.
+  fn new(plugin_info: &PluginInfo, features: &mut Features<'static>) -> Option<Self> {
     let sample_rate = plugin_info.sample_rate() as f32;
 
+    let space_echo = SpaceEcho::new(sample_rate);
+
     Some(Self {
-      space_echo: SpaceEcho::new(sample_rate),
+      meters: space_echo.meters(),
+      space_echo,
       params: Params::new(sample_rate),
+      urids: features.map.populate_collection()?,
+      time_position: TimePosition::new(),
     })
   }
 
+  fn activate(&mut self, _features: &mut Features<'static>) {
+    self.space_echo.reset();
+    self.params.reset();
+  }
+
   // Process a chunk of audio. The audio ports are dereferenced to slices, which the plugin
   // iterates over.
   fn run(&mut self, ports: &mut Ports, _features: &mut (), _sample_count: u32) {
-    self.params.set(
-      ports.input.get(),
-      ports.channel_mode.get() as i32 - 1,
-      ports.time_mode.get() as i32 - 1,
-      ports.time_link.get() == 1.,
-      ports.time_left.get(),
-      ports.time_right.get(),
-      ports.feedback.get() * 0.01,
-      ports.wow_and_flutter.get() * 0.01,
-      ports.highpass_freq.get(),
-      ports.highpass_res.get() * 0.01,
-      ports.lowpass_freq.get(),
-      ports.lowpass_res.get() * 0.01,
-      ports.reverb.get() * 0.01,
-      ports.decay.get() * 0.005,
-      ports.stereo.get() * 0.01,
-      ports.duck.get() * 0.01,
-      ports.output.get(),
-      ports.mix.get() * 0.01,
-      ports.limiter.get() == 1.,
-      ports.hold.get() == 1.,
-    );
-
-    let input_channels = ports.input_left.iter().zip(ports.input_right.iter());
-    let output_channels = ports.output_left.iter().zip(ports.output_right.iter());
+    if self.time_position.update(&ports.control, &self.urids) {
+      self.space_echo.reset();
+      self.params.reset();
+    }
+    let (time_left, time_right) = self.get_time_params(ports);
+
+    self.params.set(ParamValues {
+      input_level: ports.input.get(),
+      channel_mode: Self::get_channel_mode(ports.channel_mode.get()),
+      time_mode: Self::get_time_mode(ports.time_mode.get()),
+      head_mode: (ports.head_mode.get() as i32 - 1).into(),
+      time_link: ports.time_link.get() == 1.,
+      time_left,
+      time_right,
+      feedback: ports.feedback.get() * 0.01,
+      head_feedback: [
+        ports.head_1_feedback.get() * 0.01,
+        ports.head_2_feedback.get() * 0.01,
+        ports.head_3_feedback.get() * 0.01,
+      ],
+      wow_and_flutter: ports.wow_and_flutter.get() * 0.01,
+      highpass_freq: ports.highpass_freq.get(),
+      highpass_res: ports.highpass_res.get() * 0.01,
+      lowpass_freq: ports.lowpass_freq.get(),
+      lowpass_res: ports.lowpass_res.get() * 0.01,
+      reverb: ports.reverb.get() * 0.01,
+      decay: ports.decay_time.get(),
+      decay_low: ports.decay_low.get(),
+      decay_high: ports.decay_high.get(),
+      reverb_type: Self::get_reverb_type(ports.reverb_type.get()),
+      routing: Self::get_routing(ports.routing.get()),
+      size: ports.size.get() * 0.01,
+      predelay: ports.predelay.get(),
+      damping: ports.damping.get(),
+      modulation_rate: ports.modulation_rate.get(),
+      modulation_depth: ports.modulation_depth.get(),
+      stereo: ports.stereo.get() * 0.01,
+      duck: ports.duck.get() * 0.01,
+      duck_source: Self::get_duck_source(ports.duck_source.get()),
+      duck_target: Self::get_duck_target(ports.duck_target.get()),
+      duck_attack: ports.duck_attack.get(),
+      duck_release: ports.duck_release.get(),
+      duck_hold: ports.duck_hold.get(),
+      duck_depth: ports.duck_depth.get(),
+      duck_detector: Self::get_duck_detector(ports.duck_detector.get()),
+      duck_highpass_freq: ports.duck_highpass_freq.get(),
+      output_level: ports.output.get(),
+      mix: ports.mix.get() * 0.01,
+      limiter: ports.limiter.get() == 1.,
+      limiter_ceiling: ports.limiter_ceiling.get(),
+      limiter_release: ports.limiter_release.get(),
+      limiter_link: ports.limiter_link.get() * 0.01,
+      limiter_true_peak: ports.limiter_true_peak.get() == 1.,
+      constant_latency: ports.constant_latency.get() == 1.,
+      hold: ports.hold.get() == 1.,
+      freeze: ports.freeze.get() == 1.,
+      saturation_mode: Self::get_saturation_mode(ports.saturation_mode.get()),
+      drive: ports.drive.get() * 0.01,
+      width: ports.width.get() * 0.01,
+      bias: ports.bias.get() * 0.01,
+      oversampling: Self::get_oversampling(ports.oversampling.get()),
+      interpolation: Self::get_interpolation(ports.interpolation.get()),
+      shimmer_interval: ports.shimmer_interval.get(),
+      shimmer: ports.shimmer.get() * 0.01,
+    });
+
+    let input_channels = ports
+      .input_left
+      .chunks(MAX_BLOCK_SIZE)
+      .zip(ports.input_right.chunks(MAX_BLOCK_SIZE));
+    let output_channels = ports
+      .output_left
+      .chunks(MAX_BLOCK_SIZE)
+      .zip(ports.output_right.chunks(MAX_BLOCK_SIZE));
+    // the sidechain ports are optional, so the duck falls back to the input if they aren't connected
+    let mut sidechain_channels = ports
+      .sidechain_left
+      .as_ref()
+      .zip(ports.sidechain_right.as_ref())
+      .map(|(sidechain_left, sidechain_right)| {
+        sidechain_left
+          .chunks(MAX_BLOCK_SIZE)
+          .zip(sidechain_right.chunks(MAX_BLOCK_SIZE))
+      });
 
     for ((input_left, input_right), (output_left, output_right)) in
       input_channels.zip(output_channels)
     {
-      let output = self
-        .space_echo
-        .process((input_left.get(), input_right.get()), &mut self.params);
-      output_left.set(output.0);
-      output_right.set(output.1);
+      // The audio ports can share a buffer, so the block is copied before it's processed in place.
+      let block_size = input_left.len();
+      let mut left = [0.; MAX_BLOCK_SIZE];
+      let mut right = [0.; MAX_BLOCK_SIZE];
+      left
+        .iter_mut()
+        .zip(input_left)
+        .for_each(|(x, input)| *x = input.get());
+      right
+        .iter_mut()
+        .zip(input_right)
+        .for_each(|(x, input)| *x = input.get());
+      let mut sidechain_left = [0.; MAX_BLOCK_SIZE];
+      let mut sidechain_right = [0.; MAX_BLOCK_SIZE];
+      let sidechain = match sidechain_channels.as_mut().and_then(Iterator::next) {
+        Some((sidechain_input_left, sidechain_input_right)) => {
+          sidechain_left
+            .iter_mut()
+            .zip(sidechain_input_left)
+            .for_each(|(x, input)| *x = input.get());
+          sidechain_right
+            .iter_mut()
+            .zip(sidechain_input_right)
+            .for_each(|(x, input)| *x = input.get());
+          Some((
+            &sidechain_left[..block_size],
+            &sidechain_right[..block_size],
+          ))
+        }
+        None => None,
+      };
+
+      self.space_echo.process_block_with_sidechain(
+        &mut left[..block_size],
+        &mut right[..block_size],
+        sidechain,
+        &mut self.params,
+      );
+
+      output_left
+        .iter()
+        .zip(left)
+        .for_each(|(output, x)| output.set(x));
+      output_right
+        .iter()
+        .zip(right)
+        .for_each(|(output, x)| output.set(x));
     }
+
+    ports.latency.set(self.space_echo.latency() as f32);
+    let (input_left_level, input_right_level) = self.meters.input();
+    let (output_left_level, output_right_level) = self.meters.output();
+    ports.input_left_level.set(input_left_level);
+    ports.input_right_level.set(input_right_level);
+    ports.output_left_level.set(output_left_level);
+    ports.output_right_level.set(output_right_level);
+    ports.feedback_level.set(self.meters.feedback());
+    ports.gain_compensation.set(self.meters.gain_compensation());
+    ports
+      .duck_gain_reduction
+      .set(self.meters.duck_gain_reduction());
+    ports
+      .limiter_gain_reduction
+      .set(self.meters.limiter_gain_reduction());
   }
 }
 
@@ -0,0 +1,86 @@
+use lv2::prelude::*;
+use space_echo::sync::DEFAULT_BPM;
+
+#[uri("http://lv2plug.in/ns/ext/time#Position")]
+pub struct Position;
+
+#[uri("http://lv2plug.in/ns/ext/time#barBeat")]
+pub struct BarBeat;
+
+#[uri("http://lv2plug.in/ns/ext/time#beatsPerMinute")]
+pub struct BeatsPerMinute;
+
+#[uri("http://lv2plug.in/ns/ext/time#speed")]
+pub struct Speed;
+
+#[derive(URIDCollection)]
+pub struct TimeURIDCollection {
+  pub position: URID<Position>,
+  pub bar_beat: URID<BarBeat>,
+  pub beats_per_minute: URID<BeatsPerMinute>,
+  pub speed: URID<Speed>,
+}
+
+#[derive(URIDCollection)]
+pub struct URIDs {
+  pub atom: AtomURIDCollection,
+  pub unit: UnitURIDCollection,
+  pub time: TimeURIDCollection,
+}
+
+/// The last transport state the host sent. Hosts only send a `time:Position` when something changes, so the values are kept between runs.
+pub struct TimePosition {
+  pub bpm: f32,
+  pub speed: f32,
+  pub bar_beat: f32,
+}
+
+impl TimePosition {
+  pub fn new() -> Self {
+    Self {
+      bpm: DEFAULT_BPM,
+      speed: 0.,
+      bar_beat: 0.,
+    }
+  }
+
+  pub fn is_playing(&self) -> bool {
+    self.speed > 0.
+  }
+
+  /// Reads the time:Position objects of the sequence. Returns true when the transport started playing.
+  pub fn update(&mut self, control: &InputPort<AtomPort>, urids: &URIDs) -> bool {
+    let was_playing = self.is_playing();
+    let sequence = match control.read(urids.atom.sequence, urids.unit.beat) {
+      Some(sequence) => sequence,
+      None => return false,
+    };
+
+    for (_, atom) in sequence {
+      let (header, object_reader) = match atom.read(urids.atom.object, ()) {
+        Some(object) => object,
+        None => continue,
+      };
+      if header.otype != urids.time.position {
+        continue;
+      }
+
+      for (property_header, property) in object_reader {
+        let value = match property.read(urids.atom.float, ()) {
+          Some(value) => value,
+          None => continue,
+        };
+
+        if property_header.key == urids.time.beats_per_minute && value > 0. {
+          self.bpm = value;
+        } else if property_header.key == urids.time.speed {
+          self.speed = value;
+        } else if property_header.key == urids.time.bar_beat {
+          self.bar_beat = value;
+        }
+      }
+    }
+
+    self.is_playing() && !was_playing
+  }
+}
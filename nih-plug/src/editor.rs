@@ -1,5 +1,7 @@
 #[path = "./editor/level_controls.rs"]
 mod level_controls;
+#[path = "./editor/meters.rs"]
+mod meters;
 #[path = "./editor/reverb_filter_controls.rs"]
 mod reverb_filter_controls;
 #[path = "./editor/time_controls.rs"]
@@ -9,6 +11,7 @@ use crate::space_echo_parameters::SpaceEchoParameters;
 use nih_plug::prelude::Editor;
 use nih_plug_vizia::vizia::{model::Model, modifiers::StyleModifiers, views::HStack};
 use nih_plug_vizia::{create_vizia_editor, vizia_assets, ViziaState, ViziaTheming};
+use space_echo::Meters;
 use std::sync::Arc;
 pub use ui_data::{ParamChangeEvent, UiData};
 
@@ -16,11 +19,12 @@ const STYLE: &str = include_str!("./editor/style.css");
 
 // Makes sense to also define this here, makes it a bit easier to keep track of
 pub(crate) fn default_state() -> Arc<ViziaState> {
-  ViziaState::new(|| (800, 360))
+  ViziaState::new(|| (960, 500))
 }
 
 pub(crate) fn create(
   params: Arc<SpaceEchoParameters>,
+  meters: Arc<Meters>,
   editor_state: Arc<ViziaState>,
 ) -> Option<Box<dyn Editor>> {
   create_vizia_editor(
@@ -34,6 +38,7 @@ pub(crate) fn create(
 
       UiData {
         params: params.clone(),
+        meters: meters.clone(),
         gui_context: gui_context.clone(),
       }
       .build(cx);
@@ -42,6 +47,7 @@ pub(crate) fn create(
         time_controls::build(cx, params.clone());
         reverb_filter_controls::build(cx, params.clone());
         level_controls::build(cx, params.clone());
+        meters::build(cx);
       })
       .background_color("#161616");
     },
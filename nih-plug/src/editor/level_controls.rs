@@ -3,9 +3,13 @@ mod param_checkbox;
 use param_checkbox::ParamCheckbox;
 #[path = "./components/param_knob.rs"]
 mod param_knob;
+#[path = "./components/param_radio_button.rs"]
+mod param_radio_button;
 use super::{ParamChangeEvent, UiData};
-use crate::space_echo_parameters::SpaceEchoParameters;
-use nih_plug::params::Param;
+use crate::space_echo_parameters::{
+  DuckDetector, DuckSource, DuckTarget, Interpolation, Oversampling, SpaceEchoParameters,
+};
+use nih_plug::{params::Param, prelude::Enum};
 use nih_plug_vizia::vizia::{
   layout::Units::Auto,
   prelude::{
@@ -17,6 +21,7 @@ use nih_plug_vizia::vizia::{
   views::{HStack, Label, VStack},
 };
 use param_knob::{ParamKnob, ParamKnobSize};
+use param_radio_button::ParamRadioButton;
 use std::sync::Arc;
 
 pub fn build(cx: &mut Context, params: Arc<SpaceEchoParameters>) -> Handle<VStack> {
@@ -52,6 +57,54 @@ pub fn build(cx: &mut Context, params: Arc<SpaceEchoParameters>) -> Handle<VStac
     })
     .size(Auto);
 
+    HStack::new(cx, |cx| {
+      ParamKnob::new(
+        cx,
+        params.limiter_ceiling.name(),
+        UiData::params,
+        params.limiter_ceiling.as_ptr(),
+        |params| &params.limiter_ceiling,
+        |param_ptr, val| ParamChangeEvent::SetParam(param_ptr, val),
+        ParamKnobSize::Small,
+      );
+      ParamKnob::new(
+        cx,
+        params.limiter_release.name(),
+        UiData::params,
+        params.limiter_release.as_ptr(),
+        |params| &params.limiter_release,
+        |param_ptr, val| ParamChangeEvent::SetParam(param_ptr, val),
+        ParamKnobSize::Small,
+      );
+      ParamKnob::new(
+        cx,
+        params.limiter_link.name(),
+        UiData::params,
+        params.limiter_link.as_ptr(),
+        |params| &params.limiter_link,
+        |param_ptr, val| ParamChangeEvent::SetParam(param_ptr, val),
+        ParamKnobSize::Small,
+      );
+      ParamCheckbox::new(
+        cx,
+        params.limiter_true_peak.name(),
+        UiData::params,
+        params.limiter_true_peak.as_ptr(),
+        |params| &params.limiter_true_peak,
+        |param_ptr, val| ParamChangeEvent::SetParam(param_ptr, val),
+      );
+      ParamCheckbox::new(
+        cx,
+        params.constant_latency.name(),
+        UiData::params,
+        params.constant_latency.as_ptr(),
+        |params| &params.constant_latency,
+        |param_ptr, val| ParamChangeEvent::SetParam(param_ptr, val),
+      );
+    })
+    .size(Auto)
+    .col_between(Pixels(4.0));
+
     HStack::new(cx, |cx| {
       ParamKnob::new(
         cx,
@@ -75,6 +128,102 @@ pub fn build(cx: &mut Context, params: Arc<SpaceEchoParameters>) -> Handle<VStac
     .size(Auto)
     .col_between(Pixels(4.0));
 
+    HStack::new(cx, |cx| {
+      ParamKnob::new(
+        cx,
+        params.duck_attack.name(),
+        UiData::params,
+        params.duck_attack.as_ptr(),
+        |params| &params.duck_attack,
+        |param_ptr, val| ParamChangeEvent::SetParam(param_ptr, val),
+        ParamKnobSize::Small,
+      );
+      ParamKnob::new(
+        cx,
+        params.duck_release.name(),
+        UiData::params,
+        params.duck_release.as_ptr(),
+        |params| &params.duck_release,
+        |param_ptr, val| ParamChangeEvent::SetParam(param_ptr, val),
+        ParamKnobSize::Small,
+      );
+      ParamKnob::new(
+        cx,
+        params.duck_hold.name(),
+        UiData::params,
+        params.duck_hold.as_ptr(),
+        |params| &params.duck_hold,
+        |param_ptr, val| ParamChangeEvent::SetParam(param_ptr, val),
+        ParamKnobSize::Small,
+      );
+      ParamKnob::new(
+        cx,
+        params.duck_depth.name(),
+        UiData::params,
+        params.duck_depth.as_ptr(),
+        |params| &params.duck_depth,
+        |param_ptr, val| ParamChangeEvent::SetParam(param_ptr, val),
+        ParamKnobSize::Small,
+      );
+      ParamKnob::new(
+        cx,
+        params.duck_highpass_freq.name(),
+        UiData::params,
+        params.duck_highpass_freq.as_ptr(),
+        |params| &params.duck_highpass_freq,
+        |param_ptr, val| ParamChangeEvent::SetParam(param_ptr, val),
+        ParamKnobSize::Small,
+      );
+    })
+    .size(Auto)
+    .col_between(Pixels(4.0));
+
+    ParamRadioButton::new(
+      cx,
+      params.duck_detector.name(),
+      UiData::params,
+      params.duck_detector.as_ptr(),
+      |params| &params.duck_detector,
+      |param_ptr, val| ParamChangeEvent::SetParam(param_ptr, val),
+      DuckDetector::variants(),
+    );
+    ParamRadioButton::new(
+      cx,
+      params.duck_target.name(),
+      UiData::params,
+      params.duck_target.as_ptr(),
+      |params| &params.duck_target,
+      |param_ptr, val| ParamChangeEvent::SetParam(param_ptr, val),
+      DuckTarget::variants(),
+    );
+    ParamRadioButton::new(
+      cx,
+      params.duck_source.name(),
+      UiData::params,
+      params.duck_source.as_ptr(),
+      |params| &params.duck_source,
+      |param_ptr, val| ParamChangeEvent::SetParam(param_ptr, val),
+      DuckSource::variants(),
+    );
+    ParamRadioButton::new(
+      cx,
+      params.oversampling.name(),
+      UiData::params,
+      params.oversampling.as_ptr(),
+      |params| &params.oversampling,
+      |param_ptr, val| ParamChangeEvent::SetParam(param_ptr, val),
+      Oversampling::variants(),
+    );
+    ParamRadioButton::new(
+      cx,
+      params.interpolation.name(),
+      UiData::params,
+      params.interpolation.as_ptr(),
+      |params| &params.interpolation,
+      |param_ptr, val| ParamChangeEvent::SetParam(param_ptr, val),
+      Interpolation::variants(),
+    );
+
     VStack::new(cx, |cx| {
       Label::new(cx, "DM")
         .font_size(24.0)
@@ -0,0 +1,65 @@
+use super::UiData;
+use nih_plug_vizia::{
+  vizia::{
+    prelude::{Context, LayoutModifiers, LensExt, StyleModifiers, Units::Pixels},
+    view::Handle,
+    views::{Label, VStack},
+  },
+  widgets::PeakMeter,
+};
+use std::time::Duration;
+
+const PEAK_HOLD_TIME: Duration = Duration::from_millis(600);
+
+pub fn build(cx: &mut Context) -> Handle<VStack> {
+  VStack::new(cx, |cx| {
+    Label::new(cx, "In");
+    PeakMeter::new(
+      cx,
+      UiData::meters.map(|meters| meters.input().0),
+      Some(PEAK_HOLD_TIME),
+    );
+    PeakMeter::new(
+      cx,
+      UiData::meters.map(|meters| meters.input().1),
+      Some(PEAK_HOLD_TIME),
+    );
+
+    Label::new(cx, "Out");
+    PeakMeter::new(
+      cx,
+      UiData::meters.map(|meters| meters.output().0),
+      Some(PEAK_HOLD_TIME),
+    );
+    PeakMeter::new(
+      cx,
+      UiData::meters.map(|meters| meters.output().1),
+      Some(PEAK_HOLD_TIME),
+    );
+
+    Label::new(cx, "Feedback");
+    PeakMeter::new(
+      cx,
+      UiData::meters.map(|meters| meters.feedback()),
+      Some(PEAK_HOLD_TIME),
+    );
+
+    Label::new(
+      cx,
+      UiData::meters.map(|meters| format!("Comp {:.1} dB", meters.gain_compensation())),
+    );
+    Label::new(
+      cx,
+      UiData::meters.map(|meters| format!("Duck GR {:.1} dB", meters.duck_gain_reduction())),
+    );
+    Label::new(
+      cx,
+      UiData::meters.map(|meters| format!("Limiter GR {:.1} dB", meters.limiter_gain_reduction())),
+    );
+  })
+  .width(Pixels(160.0))
+  .child_space(Pixels(4.0))
+  .row_between(Pixels(2.0))
+  .border_width(Pixels(2.0))
+  .border_color("#2d5f4f")
+}
@@ -1,8 +1,12 @@
 #[path = "./components/param_knob.rs"]
 mod param_knob;
+#[path = "./components/param_radio_button.rs"]
+mod param_radio_button;
+#[path = "./components/param_toggle_button.rs"]
+mod param_toggle_button;
 use super::{ParamChangeEvent, UiData};
-use crate::space_echo_parameters::SpaceEchoParameters;
-use nih_plug::params::Param;
+use crate::space_echo_parameters::{ReverbType, Routing, SpaceEchoParameters};
+use nih_plug::{params::Param, prelude::Enum};
 use nih_plug_vizia::vizia::{
   layout::Units::{Auto, Stretch},
   prelude::{Context, LayoutModifiers, StyleModifiers, Units::Pixels},
@@ -10,6 +14,8 @@ use nih_plug_vizia::vizia::{
   views::{HStack, VStack},
 };
 use param_knob::{ParamKnob, ParamKnobSize};
+use param_radio_button::ParamRadioButton;
+use param_toggle_button::ParamToggleButton;
 use std::sync::Arc;
 
 pub fn build(cx: &mut Context, params: Arc<SpaceEchoParameters>) -> Handle<VStack> {
@@ -24,14 +30,44 @@ pub fn build(cx: &mut Context, params: Arc<SpaceEchoParameters>) -> Handle<VStac
         |param_ptr, val| ParamChangeEvent::SetParam(param_ptr, val),
         ParamKnobSize::Regular,
       );
-      ParamKnob::new(
+      VStack::new(cx, |cx| {
+        ParamKnob::new(
+          cx,
+          params.decay_time.name(),
+          UiData::params,
+          params.decay_time.as_ptr(),
+          |params| &params.decay_time,
+          |param_ptr, val| ParamChangeEvent::SetParam(param_ptr, val),
+          ParamKnobSize::Regular,
+        );
+        ParamToggleButton::new(
+          cx,
+          params.freeze.name(),
+          UiData::params,
+          params.freeze.as_ptr(),
+          |params| &params.freeze,
+          |param_ptr, val| ParamChangeEvent::SetParam(param_ptr, val),
+        )
+        .top(Pixels(-2.0));
+      })
+      .size(Auto);
+      ParamRadioButton::new(
         cx,
-        params.decay.name(),
+        params.reverb_type.name(),
         UiData::params,
-        params.decay.as_ptr(),
-        |params| &params.decay,
+        params.reverb_type.as_ptr(),
+        |params| &params.reverb_type,
         |param_ptr, val| ParamChangeEvent::SetParam(param_ptr, val),
-        ParamKnobSize::Regular,
+        ReverbType::variants(),
+      );
+      ParamRadioButton::new(
+        cx,
+        params.routing.name(),
+        UiData::params,
+        params.routing.as_ptr(),
+        |params| &params.routing,
+        |param_ptr, val| ParamChangeEvent::SetParam(param_ptr, val),
+        Routing::variants(),
       );
     })
     .size(Auto)
@@ -41,6 +77,76 @@ pub fn build(cx: &mut Context, params: Arc<SpaceEchoParameters>) -> Handle<VStac
     .border_top_left_radius(Pixels(8.0))
     .border_top_right_radius(Pixels(0.0));
 
+    HStack::new(cx, |cx| {
+      ParamKnob::new(
+        cx,
+        params.size.name(),
+        UiData::params,
+        params.size.as_ptr(),
+        |params| &params.size,
+        |param_ptr, val| ParamChangeEvent::SetParam(param_ptr, val),
+        ParamKnobSize::Small,
+      );
+      ParamKnob::new(
+        cx,
+        params.predelay.name(),
+        UiData::params,
+        params.predelay.as_ptr(),
+        |params| &params.predelay,
+        |param_ptr, val| ParamChangeEvent::SetParam(param_ptr, val),
+        ParamKnobSize::Small,
+      );
+      ParamKnob::new(
+        cx,
+        params.damping.name(),
+        UiData::params,
+        params.damping.as_ptr(),
+        |params| &params.damping,
+        |param_ptr, val| ParamChangeEvent::SetParam(param_ptr, val),
+        ParamKnobSize::Small,
+      );
+      ParamKnob::new(
+        cx,
+        params.decay_low.name(),
+        UiData::params,
+        params.decay_low.as_ptr(),
+        |params| &params.decay_low,
+        |param_ptr, val| ParamChangeEvent::SetParam(param_ptr, val),
+        ParamKnobSize::Small,
+      );
+      ParamKnob::new(
+        cx,
+        params.decay_high.name(),
+        UiData::params,
+        params.decay_high.as_ptr(),
+        |params| &params.decay_high,
+        |param_ptr, val| ParamChangeEvent::SetParam(param_ptr, val),
+        ParamKnobSize::Small,
+      );
+      ParamKnob::new(
+        cx,
+        params.modulation_rate.name(),
+        UiData::params,
+        params.modulation_rate.as_ptr(),
+        |params| &params.modulation_rate,
+        |param_ptr, val| ParamChangeEvent::SetParam(param_ptr, val),
+        ParamKnobSize::Small,
+      );
+      ParamKnob::new(
+        cx,
+        params.modulation_depth.name(),
+        UiData::params,
+        params.modulation_depth.as_ptr(),
+        |params| &params.modulation_depth,
+        |param_ptr, val| ParamChangeEvent::SetParam(param_ptr, val),
+        ParamKnobSize::Small,
+      );
+    })
+    .size(Auto)
+    .col_between(Pixels(4.0))
+    .child_space(Pixels(4.0))
+    .background_color("#2d5f4f");
+
     HStack::new(cx, |cx| {
       ParamKnob::new(
         cx,
@@ -9,7 +9,7 @@ mod param_radio_button;
 #[path = "./components/param_toggle_button.rs"]
 mod param_toggle_button;
 use super::{ParamChangeEvent, UiData};
-use crate::space_echo_parameters::{ChannelMode, SpaceEchoParameters, TimeMode};
+use crate::space_echo_parameters::{ChannelMode, SaturationMode, SpaceEchoParameters, TimeMode};
 use nih_plug::{params::Param, prelude::Enum};
 use nih_plug_vizia::vizia::{
   layout::Units::{Auto, Stretch},
@@ -235,6 +235,114 @@ pub fn build(cx: &mut Context, params: Arc<SpaceEchoParameters>) -> Handle<HStac
       .child_top(Pixels(8.0))
       .child_bottom(Pixels(2.0))
       .left(Stretch(1.0));
+
+      HStack::new(cx, |cx| {
+        ParamKnob::new(
+          cx,
+          params.head_mode.name(),
+          UiData::params,
+          params.head_mode.as_ptr(),
+          |params| &params.head_mode,
+          |param_ptr, val| ParamChangeEvent::SetParam(param_ptr, val),
+          ParamKnobSize::Regular,
+        );
+        ParamKnob::new(
+          cx,
+          params.head_1_feedback.name(),
+          UiData::params,
+          params.head_1_feedback.as_ptr(),
+          |params| &params.head_1_feedback,
+          |param_ptr, val| ParamChangeEvent::SetParam(param_ptr, val),
+          ParamKnobSize::Small,
+        );
+        ParamKnob::new(
+          cx,
+          params.head_2_feedback.name(),
+          UiData::params,
+          params.head_2_feedback.as_ptr(),
+          |params| &params.head_2_feedback,
+          |param_ptr, val| ParamChangeEvent::SetParam(param_ptr, val),
+          ParamKnobSize::Small,
+        );
+        ParamKnob::new(
+          cx,
+          params.head_3_feedback.name(),
+          UiData::params,
+          params.head_3_feedback.as_ptr(),
+          |params| &params.head_3_feedback,
+          |param_ptr, val| ParamChangeEvent::SetParam(param_ptr, val),
+          ParamKnobSize::Small,
+        );
+        ParamKnob::new(
+          cx,
+          params.shimmer.name(),
+          UiData::params,
+          params.shimmer.as_ptr(),
+          |params| &params.shimmer,
+          |param_ptr, val| ParamChangeEvent::SetParam(param_ptr, val),
+          ParamKnobSize::Small,
+        );
+        ParamKnob::new(
+          cx,
+          params.shimmer_interval.name(),
+          UiData::params,
+          params.shimmer_interval.as_ptr(),
+          |params| &params.shimmer_interval,
+          |param_ptr, val| ParamChangeEvent::SetParam(param_ptr, val),
+          ParamKnobSize::Small,
+        );
+      })
+      .size(Auto)
+      .col_between(Pixels(4.0))
+      .child_space(Pixels(4.0))
+      .child_top(Pixels(2.0))
+      .child_bottom(Pixels(2.0))
+      .left(Stretch(1.0));
+
+      HStack::new(cx, |cx| {
+        ParamRadioButton::new(
+          cx,
+          params.saturation_mode.name(),
+          UiData::params,
+          params.saturation_mode.as_ptr(),
+          |params| &params.saturation_mode,
+          |param_ptr, val| ParamChangeEvent::SetParam(param_ptr, val),
+          SaturationMode::variants(),
+        );
+        ParamKnob::new(
+          cx,
+          params.drive.name(),
+          UiData::params,
+          params.drive.as_ptr(),
+          |params| &params.drive,
+          |param_ptr, val| ParamChangeEvent::SetParam(param_ptr, val),
+          ParamKnobSize::Small,
+        );
+        ParamKnob::new(
+          cx,
+          params.width.name(),
+          UiData::params,
+          params.width.as_ptr(),
+          |params| &params.width,
+          |param_ptr, val| ParamChangeEvent::SetParam(param_ptr, val),
+          ParamKnobSize::Small,
+        );
+        ParamKnob::new(
+          cx,
+          params.bias.name(),
+          UiData::params,
+          params.bias.as_ptr(),
+          |params| &params.bias,
+          |param_ptr, val| ParamChangeEvent::SetParam(param_ptr, val),
+          ParamKnobSize::Small,
+        );
+      })
+      .size(Auto)
+      .col_between(Pixels(4.0))
+      .child_space(Pixels(4.0))
+      .child_top(Pixels(2.0))
+      .child_bottom(Pixels(2.0))
+      .left(Stretch(1.0));
     })
     .size(Auto)
     .border_color("#2d5f4f")
@@ -1,6 +1,7 @@
 use crate::space_echo_parameters::SpaceEchoParameters;
 use nih_plug::prelude::{GuiContext, ParamPtr};
 use nih_plug_vizia::vizia::prelude::*;
+use space_echo::Meters;
 use std::sync::Arc;
 
 pub enum ParamChangeEvent {
@@ -10,6 +11,7 @@ pub enum ParamChangeEvent {
 #[derive(Lens)]
 pub struct UiData {
   pub params: Arc<SpaceEchoParameters>,
+  pub meters: Arc<Meters>,
   pub gui_context: Arc<dyn GuiContext>,
 }
 
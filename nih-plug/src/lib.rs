@@ -1,5 +1,8 @@
 use nih_plug::prelude::*;
-use space_echo::{Params as ProcessParams, SpaceEcho};
+use space_echo::{
+  sync::{NoteDivision, SyncError, DEFAULT_BPM},
+  Meters, ParamValues, Params as ProcessParams, SpaceEcho, SpaceEchoConfig,
+};
 mod space_echo_parameters;
 use space_echo_parameters::SpaceEchoParameters;
 use std::sync::Arc;
@@ -8,16 +11,22 @@ mod editor;
 struct DmSpaceEcho {
   params: Arc<SpaceEchoParameters>,
   space_echo: SpaceEcho,
+  meters: Arc<Meters>,
   process_params: ProcessParams,
+  is_playing: bool,
+  latency: u32,
 }
 
 impl DmSpaceEcho {
   fn get_time_params(&self, context: &mut impl ProcessContext<Self>) -> (f32, f32) {
-    let bpm = context.transport().tempo.unwrap_or(120.) as f32;
-    let beat_time = 60000. / bpm;
+    let bpm = context
+      .transport()
+      .tempo
+      .map_or(DEFAULT_BPM, |tempo| tempo as f32);
 
     let time_left = if self.params.sync_left.value() {
-      self.get_synced_time(beat_time, self.params.division_left.value())
+      Self::get_synced_time(bpm, self.params.division_left.value())
+        .unwrap_or(self.params.time_left.value())
     } else {
       self.params.time_left.value()
     };
@@ -27,44 +36,30 @@ impl DmSpaceEcho {
       self.params.sync_right.value(),
     ) {
       (true, _) => time_left,
-      (false, true) => self.get_synced_time(beat_time, self.params.division_right.value()),
+      (false, true) => Self::get_synced_time(bpm, self.params.division_right.value())
+        .unwrap_or(self.params.time_right.value()),
       (false, false) => self.params.time_right.value(),
     };
 
     (time_left, time_right)
   }
 
-  fn get_synced_time(&self, beat_time: f32, division: i32) -> f32 {
-    let factor = match division {
-      0 => 0.125,
-      1 => 0.166666666666667,
-      2 => 0.1875,
-      3 => 0.25,
-      4 => 0.333333333333333,
-      5 => 0.375,
-      6 => 0.5,
-      7 => 0.666666666666667,
-      8 => 0.75,
-      9 => 1.,
-      10 => 1.333333333333333,
-      11 => 1.5,
-      12 => 2.,
-      13 => 2.666666666666667,
-      14 => 3.,
-      15 => 4.,
-      _ => panic!("synced_time value is out of range."),
-    };
-    beat_time * factor
+  fn get_synced_time(bpm: f32, division: i32) -> Result<f32, SyncError> {
+    NoteDivision::try_from(division).map(|division| division.to_ms(bpm))
   }
 }
 
 impl Default for DmSpaceEcho {
   fn default() -> Self {
     let params = Arc::new(SpaceEchoParameters::default());
+    let space_echo = SpaceEcho::with_config(44100., SpaceEchoConfig::default());
     Self {
       params: params.clone(),
-      space_echo: SpaceEcho::new(44100.),
+      meters: space_echo.meters(),
+      space_echo,
       process_params: ProcessParams::new(44100.),
+      is_playing: false,
+      latency: 0,
     }
   }
 }
@@ -76,11 +71,24 @@ impl Plugin for DmSpaceEcho {
   const EMAIL: &'static str = "davemollen@gmail.com";
   const VERSION: &'static str = env!("CARGO_PKG_VERSION");
 
-  const AUDIO_IO_LAYOUTS: &'static [AudioIOLayout] = &[AudioIOLayout {
-    main_input_channels: NonZeroU32::new(2),
-    main_output_channels: NonZeroU32::new(2),
-    ..AudioIOLayout::const_default()
-  }];
+  // The first layout has a stereo sidechain for the duck. The second one is for hosts without sidechain support.
+  const AUDIO_IO_LAYOUTS: &'static [AudioIOLayout] = &[
+    AudioIOLayout {
+      main_input_channels: NonZeroU32::new(2),
+      main_output_channels: NonZeroU32::new(2),
+      aux_input_ports: &[new_nonzero_u32(2)],
+      names: PortNames {
+        aux_inputs: &["Sidechain"],
+        ..PortNames::const_default()
+      },
+      ..AudioIOLayout::const_default()
+    },
+    AudioIOLayout {
+      main_input_channels: NonZeroU32::new(2),
+      main_output_channels: NonZeroU32::new(2),
+      ..AudioIOLayout::const_default()
+    },
+  ];
   const MIDI_INPUT: MidiConfig = MidiConfig::None;
   const SAMPLE_ACCURATE_AUTOMATION: bool = true;
 
@@ -95,7 +103,11 @@ impl Plugin for DmSpaceEcho {
   }
 
   fn editor(&mut self, _async_executor: AsyncExecutor<Self>) -> Option<Box<dyn Editor>> {
-    editor::create(self.params.clone(), self.params.editor_state.clone())
+    editor::create(
+      self.params.clone(),
+      self.meters.clone(),
+      self.params.editor_state.clone(),
+    )
   }
 
   fn initialize(
@@ -104,50 +116,122 @@ impl Plugin for DmSpaceEcho {
     buffer_config: &BufferConfig,
     _context: &mut impl InitContext<Self>,
   ) -> bool {
-    self.space_echo = SpaceEcho::new(buffer_config.sample_rate);
-    self.process_params = ProcessParams::new(buffer_config.sample_rate);
+    let sample_rate = buffer_config.sample_rate;
+    if sample_rate > self.space_echo.config().max_sample_rate {
+      self.space_echo = SpaceEcho::with_config(
+        sample_rate,
+        SpaceEchoConfig {
+          max_sample_rate: sample_rate,
+          ..Default::default()
+        },
+      );
+      self.space_echo.set_meters(self.meters.clone());
+    } else {
+      self.space_echo.set_sample_rate(sample_rate);
+    }
+    self.process_params.set_sample_rate(sample_rate);
     true
   }
 
+  fn reset(&mut self) {
+    self.space_echo.reset();
+    self.process_params.reset();
+  }
+
   fn process(
     &mut self,
     buffer: &mut Buffer,
-    _aux: &mut AuxiliaryBuffers,
+    aux: &mut AuxiliaryBuffers,
     context: &mut impl ProcessContext<Self>,
   ) -> ProcessStatus {
+    let is_playing = context.transport().playing;
+    if is_playing && !self.is_playing {
+      self.reset();
+    }
+    self.is_playing = is_playing;
+
     let (time_left, time_right) = self.get_time_params(context);
-    self.process_params.set(
-      self.params.input.value(),
-      self.params.channel_mode.value() as i32,
-      self.params.time_mode.value() as i32,
-      self.params.time_link.value(),
+    self.process_params.set(ParamValues {
+      input_level: self.params.input.value(),
+      channel_mode: self.params.channel_mode.value().into(),
+      time_mode: self.params.time_mode.value().into(),
+      head_mode: self.params.head_mode.value().into(),
+      time_link: self.params.time_link.value(),
       time_left,
       time_right,
-      self.params.feedback.value(),
-      self.params.wow_and_flutter.value(),
-      self.params.highpass_freq.value(),
-      self.params.highpass_res.value(),
-      self.params.lowpass_freq.value(),
-      self.params.lowpass_res.value(),
-      self.params.reverb.value(),
-      self.params.decay.value(),
-      self.params.stereo.value(),
-      self.params.duck.value(),
-      self.params.output.value(),
-      self.params.mix.value(),
-      self.params.limiter.value(),
-      self.params.hold.value(),
-    );
-
-    buffer.iter_samples().for_each(|mut channel_samples| {
-      let channel_iterator = &mut channel_samples.iter_mut();
-      let left_channel = channel_iterator.next().unwrap();
-      let right_channel = channel_iterator.next().unwrap();
-
-      (*left_channel, *right_channel) = self
-        .space_echo
-        .process((*left_channel, *right_channel), &mut self.process_params);
+      feedback: self.params.feedback.value(),
+      head_feedback: [
+        self.params.head_1_feedback.value(),
+        self.params.head_2_feedback.value(),
+        self.params.head_3_feedback.value(),
+      ],
+      wow_and_flutter: self.params.wow_and_flutter.value(),
+      highpass_freq: self.params.highpass_freq.value(),
+      highpass_res: self.params.highpass_res.value(),
+      lowpass_freq: self.params.lowpass_freq.value(),
+      lowpass_res: self.params.lowpass_res.value(),
+      reverb: self.params.reverb.value(),
+      decay: self.params.decay_time.value(),
+      decay_low: self.params.decay_low.value(),
+      decay_high: self.params.decay_high.value(),
+      reverb_type: self.params.reverb_type.value().into(),
+      routing: self.params.routing.value().into(),
+      size: self.params.size.value(),
+      predelay: self.params.predelay.value(),
+      damping: self.params.damping.value(),
+      modulation_rate: self.params.modulation_rate.value(),
+      modulation_depth: self.params.modulation_depth.value(),
+      stereo: self.params.stereo.value(),
+      duck: self.params.duck.value(),
+      duck_source: self.params.duck_source.value().into(),
+      duck_target: self.params.duck_target.value().into(),
+      duck_attack: self.params.duck_attack.value(),
+      duck_release: self.params.duck_release.value(),
+      duck_hold: self.params.duck_hold.value(),
+      duck_depth: self.params.duck_depth.value(),
+      duck_detector: self.params.duck_detector.value().into(),
+      duck_highpass_freq: self.params.duck_highpass_freq.value(),
+      output_level: self.params.output.value(),
+      mix: self.params.mix.value(),
+      limiter: self.params.limiter.value(),
+      limiter_ceiling: self.params.limiter_ceiling.value(),
+      limiter_release: self.params.limiter_release.value(),
+      limiter_link: self.params.limiter_link.value(),
+      limiter_true_peak: self.params.limiter_true_peak.value(),
+      constant_latency: self.params.constant_latency.value(),
+      hold: self.params.hold.value(),
+      freeze: self.params.freeze.value(),
+      saturation_mode: self.params.saturation_mode.value().into(),
+      drive: self.params.drive.value(),
+      width: self.params.width.value(),
+      bias: self.params.bias.value(),
+      shimmer_interval: self.params.shimmer_interval.value(),
+      shimmer: self.params.shimmer.value(),
+      oversampling: self.params.oversampling.value().into(),
+      interpolation: self.params.interpolation.value().into(),
     });
+
+    let sidechain = aux
+      .inputs
+      .first()
+      .and_then(|sidechain| match sidechain.as_slice_immutable() {
+        [sidechain_left, sidechain_right, ..] => Some((&**sidechain_left, &**sidechain_right)),
+        _ => None,
+      });
+    if let [left_channel, right_channel, ..] = buffer.as_slice() {
+      self.space_echo.process_block_with_sidechain(
+        left_channel,
+        right_channel,
+        sidechain,
+        &mut self.process_params,
+      );
+    }
+
+    let latency = self.space_echo.latency() as u32;
+    if latency != self.latency {
+      context.set_latency_samples(latency);
+      self.latency = latency;
+    }
     ProcessStatus::Normal
   }
 
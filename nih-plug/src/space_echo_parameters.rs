@@ -9,8 +9,11 @@ use nih_plug::{
 use std::sync::Arc;
 mod custom_formatters;
 use crate::editor;
-use custom_formatters::{s2v_f32_synced_time, v2s_f32_synced_time};
+use custom_formatters::{
+  s2v_f32_synced_time, s2v_i32_head_mode, v2s_f32_synced_time, v2s_i32_head_mode,
+};
 use nih_plug_vizia::ViziaState;
+use space_echo::{DEFAULT_MAX_REVERB_SIZE, MAX_MODULATION_DEPTH_IN_MS, MAX_PREDELAY_IN_MS};
 
 #[derive(Enum, PartialEq)]
 pub enum ChannelMode {
@@ -23,6 +26,169 @@ pub enum ChannelMode {
 pub enum TimeMode {
   Repitch,
   Fade,
+  Reverse,
+}
+
+#[derive(Enum, PartialEq)]
+pub enum ReverbType {
+  Hall,
+  Spring,
+}
+
+#[derive(Enum, PartialEq)]
+pub enum Routing {
+  #[name = "Echo > Reverb"]
+  EchoIntoReverb,
+  #[name = "Reverb > Echo"]
+  ReverbIntoEcho,
+  Parallel,
+  #[name = "Dry Reverb"]
+  ReverbOnDryOnly,
+}
+
+#[derive(Enum, PartialEq)]
+pub enum DuckSource {
+  Input,
+  Sidechain,
+}
+
+#[derive(Enum, PartialEq)]
+pub enum DuckTarget {
+  Wet,
+  Echo,
+  Reverb,
+  Feedback,
+}
+
+#[derive(Enum, PartialEq)]
+pub enum DuckDetector {
+  Peak,
+  #[name = "RMS"]
+  Rms,
+}
+
+#[derive(Enum, PartialEq)]
+pub enum SaturationMode {
+  Cheap,
+  Hysteresis,
+}
+
+#[derive(Enum, PartialEq)]
+pub enum Oversampling {
+  #[name = "1x"]
+  X1,
+  #[name = "2x"]
+  X2,
+  #[name = "4x"]
+  X4,
+}
+
+#[derive(Enum, PartialEq)]
+pub enum Interpolation {
+  Step,
+  Linear,
+  Cosine,
+  Cubic,
+  Spline,
+}
+
+impl From<ChannelMode> for space_echo::ChannelMode {
+  fn from(channel_mode: ChannelMode) -> Self {
+    match channel_mode {
+      ChannelMode::Stereo => Self::Stereo,
+      ChannelMode::PingPong => Self::PingPong,
+    }
+  }
+}
+
+impl From<TimeMode> for space_echo::TimeMode {
+  fn from(time_mode: TimeMode) -> Self {
+    match time_mode {
+      TimeMode::Repitch => Self::Repitch,
+      TimeMode::Fade => Self::Fade,
+      TimeMode::Reverse => Self::Reverse,
+    }
+  }
+}
+
+impl From<ReverbType> for space_echo::ReverbType {
+  fn from(reverb_type: ReverbType) -> Self {
+    match reverb_type {
+      ReverbType::Hall => Self::Hall,
+      ReverbType::Spring => Self::Spring,
+    }
+  }
+}
+
+impl From<Routing> for space_echo::Routing {
+  fn from(routing: Routing) -> Self {
+    match routing {
+      Routing::EchoIntoReverb => Self::EchoIntoReverb,
+      Routing::ReverbIntoEcho => Self::ReverbIntoEcho,
+      Routing::Parallel => Self::Parallel,
+      Routing::ReverbOnDryOnly => Self::ReverbOnDryOnly,
+    }
+  }
+}
+
+impl From<DuckSource> for space_echo::DuckSource {
+  fn from(duck_source: DuckSource) -> Self {
+    match duck_source {
+      DuckSource::Input => Self::Input,
+      DuckSource::Sidechain => Self::Sidechain,
+    }
+  }
+}
+
+impl From<DuckTarget> for space_echo::DuckTarget {
+  fn from(duck_target: DuckTarget) -> Self {
+    match duck_target {
+      DuckTarget::Wet => Self::Wet,
+      DuckTarget::Echo => Self::Echo,
+      DuckTarget::Reverb => Self::Reverb,
+      DuckTarget::Feedback => Self::Feedback,
+    }
+  }
+}
+
+impl From<DuckDetector> for space_echo::DuckDetector {
+  fn from(duck_detector: DuckDetector) -> Self {
+    match duck_detector {
+      DuckDetector::Peak => Self::Peak,
+      DuckDetector::Rms => Self::Rms,
+    }
+  }
+}
+
+impl From<SaturationMode> for space_echo::SaturationMode {
+  fn from(saturation_mode: SaturationMode) -> Self {
+    match saturation_mode {
+      SaturationMode::Cheap => Self::Cheap,
+      SaturationMode::Hysteresis => Self::Hysteresis,
+    }
+  }
+}
+
+impl From<Oversampling> for space_echo::Oversampling {
+  fn from(oversampling: Oversampling) -> Self {
+    match oversampling {
+      Oversampling::X1 => Self::X1,
+      Oversampling::X2 => Self::X2,
+      Oversampling::X4 => Self::X4,
+    }
+  }
+}
+
+impl From<Interpolation> for space_echo::Interpolation {
+  fn from(interpolation: Interpolation) -> Self {
+    match interpolation {
+      Interpolation::Step => Self::Step,
+      Interpolation::Linear => Self::Linear,
+      Interpolation::Cosine => Self::Cosine,
+      Interpolation::Cubic => Self::Cubic,
+      Interpolation::Spline => Self::Spline,
+    }
+  }
 }
 
 #[derive(Params)]
@@ -39,6 +205,9 @@ pub struct SpaceEchoParameters {
   #[id = "time_mode"]
   pub time_mode: EnumParam<TimeMode>,
 
+  #[id = "head_mode"]
+  pub head_mode: IntParam,
+
   #[id = "time_link"]
   pub time_link: BoolParam,
 
@@ -63,6 +232,15 @@ pub struct SpaceEchoParameters {
   #[id = "feedback"]
   pub feedback: FloatParam,
 
+  #[id = "head_1_feedback"]
+  pub head_1_feedback: FloatParam,
+
+  #[id = "head_2_feedback"]
+  pub head_2_feedback: FloatParam,
+
+  #[id = "head_3_feedback"]
+  pub head_3_feedback: FloatParam,
+
   #[id = "wow_and_flutter"]
   pub wow_and_flutter: FloatParam,
 
@@ -81,8 +259,35 @@ pub struct SpaceEchoParameters {
   #[id = "reverb"]
   pub reverb: FloatParam,
 
-  #[id = "decay"]
-  pub decay: FloatParam,
+  #[id = "decay_time"]
+  pub decay_time: FloatParam,
+
+  #[id = "decay_low"]
+  pub decay_low: FloatParam,
+
+  #[id = "decay_high"]
+  pub decay_high: FloatParam,
+
+  #[id = "reverb_type"]
+  pub reverb_type: EnumParam<ReverbType>,
+
+  #[id = "routing"]
+  pub routing: EnumParam<Routing>,
+
+  #[id = "size"]
+  pub size: FloatParam,
+
+  #[id = "predelay"]
+  pub predelay: FloatParam,
+
+  #[id = "damping"]
+  pub damping: FloatParam,
+
+  #[id = "modulation_rate"]
+  pub modulation_rate: FloatParam,
+
+  #[id = "modulation_depth"]
+  pub modulation_depth: FloatParam,
 
   #[id = "stereo"]
   pub stereo: FloatParam,
@@ -90,6 +295,30 @@ pub struct SpaceEchoParameters {
   #[id = "duck"]
   pub duck: FloatParam,
 
+  #[id = "duck_source"]
+  pub duck_source: EnumParam<DuckSource>,
+
+  #[id = "duck_target"]
+  pub duck_target: EnumParam<DuckTarget>,
+
+  #[id = "duck_attack"]
+  pub duck_attack: FloatParam,
+
+  #[id = "duck_release"]
+  pub duck_release: FloatParam,
+
+  #[id = "duck_hold"]
+  pub duck_hold: FloatParam,
+
+  #[id = "duck_depth"]
+  pub duck_depth: FloatParam,
+
+  #[id = "duck_detector"]
+  pub duck_detector: EnumParam<DuckDetector>,
+
+  #[id = "duck_highpass_freq"]
+  pub duck_highpass_freq: FloatParam,
+
   #[id = "output"]
   pub output: FloatParam,
 
@@ -99,8 +328,50 @@ pub struct SpaceEchoParameters {
   #[id = "limiter"]
   pub limiter: BoolParam,
 
+  #[id = "limiter_ceiling"]
+  pub limiter_ceiling: FloatParam,
+
+  #[id = "limiter_release"]
+  pub limiter_release: FloatParam,
+
+  #[id = "limiter_link"]
+  pub limiter_link: FloatParam,
+
+  #[id = "limiter_true_peak"]
+  pub limiter_true_peak: BoolParam,
+
+  #[id = "constant_latency"]
+  pub constant_latency: BoolParam,
+
   #[id = "hold"]
   pub hold: BoolParam,
+
+  #[id = "freeze"]
+  pub freeze: BoolParam,
+
+  #[id = "saturation_mode"]
+  pub saturation_mode: EnumParam<SaturationMode>,
+
+  #[id = "drive"]
+  pub drive: FloatParam,
+
+  #[id = "width"]
+  pub width: FloatParam,
+
+  #[id = "bias"]
+  pub bias: FloatParam,
+
+  #[id = "shimmer_interval"]
+  pub shimmer_interval: FloatParam,
+
+  #[id = "shimmer"]
+  pub shimmer: FloatParam,
+
+  #[id = "oversampling"]
+  pub oversampling: EnumParam<Oversampling>,
+
+  #[id = "interpolation"]
+  pub interpolation: EnumParam<Interpolation>,
 }
 
 impl Default for SpaceEchoParameters {
@@ -123,6 +394,10 @@ impl Default for SpaceEchoParameters {
 
       time_mode: EnumParam::new("Time Mode", TimeMode::Repitch),
 
+      head_mode: IntParam::new("Head Mode", 0, IntRange::Linear { min: 0, max: 12 })
+        .with_value_to_string(v2s_i32_head_mode())
+        .with_string_to_value(s2v_i32_head_mode()),
+
       sync_left: BoolParam::new("Sync Left", false),
 
       sync_right: BoolParam::new("Sync Right", false),
@@ -166,6 +441,21 @@ impl Default for SpaceEchoParameters {
         .with_value_to_string(v2s_f32_percentage(2))
         .with_string_to_value(s2v_f32_percentage()),
 
+      head_1_feedback: FloatParam::new("Head 1", 1., FloatRange::Linear { min: 0., max: 1. })
+        .with_unit(" %")
+        .with_value_to_string(v2s_f32_percentage(2))
+        .with_string_to_value(s2v_f32_percentage()),
+
+      head_2_feedback: FloatParam::new("Head 2", 1., FloatRange::Linear { min: 0., max: 1. })
+        .with_unit(" %")
+        .with_value_to_string(v2s_f32_percentage(2))
+        .with_string_to_value(s2v_f32_percentage()),
+
+      head_3_feedback: FloatParam::new("Head 3", 1., FloatRange::Linear { min: 0., max: 1. })
+        .with_unit(" %")
+        .with_value_to_string(v2s_f32_percentage(2))
+        .with_string_to_value(s2v_f32_percentage()),
+
       wow_and_flutter: FloatParam::new("Flutter", 0., FloatRange::Linear { min: 0., max: 1. })
         .with_unit(" %")
         .with_value_to_string(v2s_f32_percentage(2))
@@ -210,10 +500,104 @@ impl Default for SpaceEchoParameters {
         .with_value_to_string(v2s_f32_percentage(2))
         .with_string_to_value(s2v_f32_percentage()),
 
-      decay: FloatParam::new("Decay", 0.5, FloatRange::Linear { min: 0., max: 1. })
-        .with_unit(" %")
-        .with_value_to_string(v2s_f32_percentage(2))
-        .with_string_to_value(s2v_f32_percentage()),
+      decay_time: FloatParam::new(
+        "Decay",
+        1.5,
+        FloatRange::Skewed {
+          min: 0.1,
+          max: 20.,
+          factor: 0.3,
+        },
+      )
+      .with_unit(" s")
+      .with_value_to_string(v2s_f32_rounded(2)),
+
+      decay_low: FloatParam::new(
+        "Low Decay",
+        1.,
+        FloatRange::Skewed {
+          min: 0.25,
+          max: 4.,
+          factor: 0.5,
+        },
+      )
+      .with_unit(" x")
+      .with_value_to_string(v2s_f32_rounded(2)),
+
+      decay_high: FloatParam::new(
+        "High Decay",
+        1.,
+        FloatRange::Skewed {
+          min: 0.25,
+          max: 4.,
+          factor: 0.5,
+        },
+      )
+      .with_unit(" x")
+      .with_value_to_string(v2s_f32_rounded(2)),
+
+      reverb_type: EnumParam::new("Reverb Type", ReverbType::Hall),
+
+      routing: EnumParam::new("Routing", Routing::EchoIntoReverb),
+
+      size: FloatParam::new(
+        "Size",
+        1.,
+        FloatRange::Linear {
+          min: 0.25,
+          max: DEFAULT_MAX_REVERB_SIZE,
+        },
+      )
+      .with_unit(" %")
+      .with_value_to_string(v2s_f32_percentage(2))
+      .with_string_to_value(s2v_f32_percentage()),
+
+      predelay: FloatParam::new(
+        "Pre-delay",
+        0.,
+        FloatRange::Skewed {
+          min: 0.,
+          max: MAX_PREDELAY_IN_MS,
+          factor: 0.5,
+        },
+      )
+      .with_unit(" ms")
+      .with_value_to_string(v2s_f32_rounded(2)),
+
+      damping: FloatParam::new(
+        "Damping",
+        6000.,
+        FloatRange::Skewed {
+          min: 500.,
+          max: 20000.,
+          factor: 0.3,
+        },
+      )
+      .with_value_to_string(v2s_f32_hz_then_khz(2))
+      .with_string_to_value(s2v_f32_hz_then_khz()),
+
+      modulation_rate: FloatParam::new(
+        "Rate",
+        3.7,
+        FloatRange::Skewed {
+          min: 0.1,
+          max: 10.,
+          factor: 0.5,
+        },
+      )
+      .with_unit(" Hz")
+      .with_value_to_string(v2s_f32_rounded(2)),
+
+      modulation_depth: FloatParam::new(
+        "Depth",
+        1.,
+        FloatRange::Linear {
+          min: 0.,
+          max: MAX_MODULATION_DEPTH_IN_MS,
+        },
+      )
+      .with_unit(" ms")
+      .with_value_to_string(v2s_f32_rounded(2)),
 
       stereo: FloatParam::new("Stereo", 1., FloatRange::Linear { min: 0., max: 1. })
         .with_unit(" %")
@@ -225,6 +609,64 @@ impl Default for SpaceEchoParameters {
         .with_value_to_string(v2s_f32_percentage(2))
         .with_string_to_value(s2v_f32_percentage()),
 
+      duck_source: EnumParam::new("Duck Source", DuckSource::Input),
+
+      duck_target: EnumParam::new("Duck Target", DuckTarget::Wet),
+
+      duck_attack: FloatParam::new(
+        "Duck Attack",
+        1.5,
+        FloatRange::Skewed {
+          min: 0.1,
+          max: 100.,
+          factor: 0.3,
+        },
+      )
+      .with_unit(" ms")
+      .with_value_to_string(v2s_f32_rounded(2)),
+
+      duck_release: FloatParam::new(
+        "Duck Release",
+        120.,
+        FloatRange::Skewed {
+          min: 10.,
+          max: 2000.,
+          factor: 0.3,
+        },
+      )
+      .with_unit(" ms")
+      .with_value_to_string(v2s_f32_rounded(2)),
+
+      duck_hold: FloatParam::new(
+        "Duck Hold",
+        0.,
+        FloatRange::Skewed {
+          min: 0.,
+          max: 500.,
+          factor: 0.5,
+        },
+      )
+      .with_unit(" ms")
+      .with_value_to_string(v2s_f32_rounded(2)),
+
+      duck_depth: FloatParam::new("Duck Depth", 60., FloatRange::Linear { min: 0., max: 60. })
+        .with_unit(" dB")
+        .with_value_to_string(v2s_f32_rounded(2)),
+
+      duck_detector: EnumParam::new("Duck Detector", DuckDetector::Peak),
+
+      duck_highpass_freq: FloatParam::new(
+        "Duck HPF",
+        20.,
+        FloatRange::Skewed {
+          min: 20.,
+          max: 2000.,
+          factor: 0.3,
+        },
+      )
+      .with_value_to_string(v2s_f32_hz_then_khz(2))
+      .with_string_to_value(s2v_f32_hz_then_khz()),
+
       output: FloatParam::new(
         "Output",
         0.,
@@ -250,7 +692,76 @@ impl Default for SpaceEchoParameters {
 
       limiter: BoolParam::new("Limiter", false),
 
+      limiter_ceiling: FloatParam::new(
+        "Limiter Ceiling",
+        -0.3,
+        FloatRange::Linear { min: -24., max: 0. },
+      )
+      .with_unit(" dB")
+      .with_value_to_string(v2s_f32_rounded(2)),
+
+      limiter_release: FloatParam::new(
+        "Limiter Release",
+        40.,
+        FloatRange::Skewed {
+          min: 1.,
+          max: 1000.,
+          factor: 0.3,
+        },
+      )
+      .with_unit(" ms")
+      .with_value_to_string(v2s_f32_rounded(2)),
+
+      limiter_link: FloatParam::new("Limiter Link", 1., FloatRange::Linear { min: 0., max: 1. })
+        .with_unit(" %")
+        .with_value_to_string(v2s_f32_percentage(2))
+        .with_string_to_value(s2v_f32_percentage()),
+
+      limiter_true_peak: BoolParam::new("True Peak", false),
+
+      constant_latency: BoolParam::new("Constant Latency", false),
+
       hold: BoolParam::new("Hold", false),
+
+      freeze: BoolParam::new("Freeze", false),
+
+      saturation_mode: EnumParam::new("Saturation", SaturationMode::Cheap),
+
+      drive: FloatParam::new("Drive", 0.5, FloatRange::Linear { min: 0., max: 1. })
+        .with_unit(" %")
+        .with_value_to_string(v2s_f32_percentage(2))
+        .with_string_to_value(s2v_f32_percentage()),
+
+      width: FloatParam::new("Width", 0.5, FloatRange::Linear { min: 0., max: 1. })
+        .with_unit(" %")
+        .with_value_to_string(v2s_f32_percentage(2))
+        .with_string_to_value(s2v_f32_percentage()),
+
+      bias: FloatParam::new("Bias", 0., FloatRange::Linear { min: 0., max: 1. })
+        .with_unit(" %")
+        .with_value_to_string(v2s_f32_percentage(2))
+        .with_string_to_value(s2v_f32_percentage()),
+
+      shimmer_interval: FloatParam::new(
+        "Interval",
+        12.,
+        FloatRange::Linear {
+          min: -12.,
+          max: 12.,
+        },
+      )
+      .with_step_size(1.)
+      .with_unit(" st")
+      .with_value_to_string(v2s_f32_rounded(0)),
+
+      shimmer: FloatParam::new("Shimmer", 0., FloatRange::Linear { min: 0., max: 1. })
+        .with_unit(" %")
+        .with_value_to_string(v2s_f32_percentage(2))
+        .with_string_to_value(s2v_f32_percentage()),
+
+      oversampling: EnumParam::new("Quality", Oversampling::X1),
+
+      interpolation: EnumParam::new("Interpolation", Interpolation::Linear),
     }
   }
 }
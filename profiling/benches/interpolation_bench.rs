@@ -0,0 +1,59 @@
+#[path = "../src/utils.rs"]
+mod utils;
+use criterion::{criterion_group, criterion_main, Criterion};
+use space_echo::{Interpolation, ParamValues, Params, SpaceEcho};
+use utils::generate_signal_stream;
+
+fn interpolation_bench(c: &mut Criterion) {
+  let signal_left = generate_signal_stream(44100);
+  let signal_right = generate_signal_stream(44100);
+  let mut left = vec![0.; 512];
+  let mut right = vec![0.; 512];
+  let mut group = c.benchmark_group("interpolation");
+
+  for interpolation in [
+    Interpolation::Step,
+    Interpolation::Linear,
+    Interpolation::Cosine,
+    Interpolation::Cubic,
+    Interpolation::Spline,
+  ] {
+    let mut space_echo = SpaceEcho::new(44100.);
+    let mut params = Params::new(44100.);
+    params.set(ParamValues {
+      input_level: 1.,
+      time_link: false,
+      feedback: 0.8,
+      wow_and_flutter: 0.2,
+      highpass_freq: 40.,
+      highpass_res: 0.1,
+      lowpass_res: 0.1,
+      reverb: 0.5,
+      decay: 0.8,
+      output_level: 1.,
+      limiter: true,
+      interpolation,
+      ..Default::default()
+    });
+
+    group.bench_function(format!("{:?}", interpolation), |b| {
+      b.iter(|| {
+        for (signal_left, signal_right) in signal_left.chunks(512).zip(signal_right.chunks(512)) {
+          let block_size = signal_left.len();
+          left[..block_size].copy_from_slice(signal_left);
+          right[..block_size].copy_from_slice(signal_right);
+          space_echo.process_block(
+            &mut left[..block_size],
+            &mut right[..block_size],
+            &mut params,
+          );
+        }
+      })
+    });
+  }
+
+  group.finish();
+}
+
+criterion_group!(benches, interpolation_bench);
+criterion_main!(benches);
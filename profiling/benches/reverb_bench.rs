@@ -1,7 +1,7 @@
 #[path = "../src/utils.rs"]
 mod utils;
 use criterion::{criterion_group, criterion_main, Criterion};
-use space_echo::Reverb;
+use space_echo::{Reverb, ReverbType};
 use utils::generate_stereo_signal_stream;
 
 fn reverb_bench(c: &mut Criterion) {
@@ -17,5 +17,19 @@ fn reverb_bench(c: &mut Criterion) {
   });
 }
 
-criterion_group!(benches, reverb_bench);
+fn spring_reverb_bench(c: &mut Criterion) {
+  let mut reverb = Reverb::new(44100.);
+  reverb.set_reverb_type(ReverbType::Spring);
+  let signal_stream = generate_stereo_signal_stream(44100);
+
+  c.bench_function("spring_reverb", |b| {
+    b.iter(|| {
+      for signal in &signal_stream {
+        reverb.process(*signal, 0.5, 0.8);
+      }
+    })
+  });
+}
+
+criterion_group!(benches, reverb_bench, spring_reverb_bench);
 criterion_main!(benches);
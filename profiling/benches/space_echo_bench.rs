@@ -1,25 +1,64 @@
 #[path = "../src/utils.rs"]
 mod utils;
 use criterion::{criterion_group, criterion_main, Criterion};
-use space_echo::{Params, SpaceEcho};
-use utils::generate_stereo_signal_stream;
+use space_echo::{ParamValues, Params, SpaceEcho};
+use utils::{generate_signal_stream, generate_stereo_signal_stream};
 
-fn space_echo_bench(c: &mut Criterion) {
-  let mut space_echo = SpaceEcho::new(44100.);
+fn create_space_echo() -> (SpaceEcho, Params) {
+  let space_echo = SpaceEcho::new(44100.);
   let mut params = Params::new(44100.);
-  params.set(
-    1., 0, 0, false, 250., 250., 0.8, 0.2, 40., 0.1, 6000., 0.1, 0.5, 0.8, 1., 0., 1., 0.5, true,
-    false,
-  );
-  let signal_stream = generate_stereo_signal_stream(44100);
+  params.set(ParamValues {
+    input_level: 1.,
+    time_link: false,
+    feedback: 0.8,
+    wow_and_flutter: 0.2,
+    highpass_freq: 40.,
+    highpass_res: 0.1,
+    lowpass_res: 0.1,
+    reverb: 0.5,
+    decay: 0.8,
+    output_level: 1.,
+    limiter: true,
+    ..Default::default()
+  });
+  (space_echo, params)
+}
 
-  c.bench_function("space_echo", |b| {
+// Compares processing one sample at a time with processing blocks of 512 samples, to show the saving of the block-wide stages.
+fn space_echo_bench(c: &mut Criterion) {
+  let mut group = c.benchmark_group("space_echo");
+
+  let (mut space_echo, mut params) = create_space_echo();
+  let signal_stream = generate_stereo_signal_stream(44100);
+  group.bench_function("process", |b| {
     b.iter(|| {
       for signal in &signal_stream {
         space_echo.process(*signal, &mut params);
       }
     })
   });
+
+  let (mut space_echo, mut params) = create_space_echo();
+  let signal_left = generate_signal_stream(44100);
+  let signal_right = generate_signal_stream(44100);
+  let mut left = vec![0.; 512];
+  let mut right = vec![0.; 512];
+  group.bench_function("process_block", |b| {
+    b.iter(|| {
+      for (signal_left, signal_right) in signal_left.chunks(512).zip(signal_right.chunks(512)) {
+        let block_size = signal_left.len();
+        left[..block_size].copy_from_slice(signal_left);
+        right[..block_size].copy_from_slice(signal_right);
+        space_echo.process_block(
+          &mut left[..block_size],
+          &mut right[..block_size],
+          &mut params,
+        );
+      }
+    })
+  });
+
+  group.finish();
 }
 
 criterion_group!(benches, space_echo_bench);
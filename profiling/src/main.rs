@@ -1,17 +1,31 @@
 mod utils;
-use space_echo::{Params, SpaceEcho};
+use space_echo::{ParamValues, Params, SpaceEcho};
 use utils::generate_signal;
 
 fn main() {
   let mut space_echo = SpaceEcho::new(44100.);
   let mut params = Params::new(44100.);
-  params.set(
-    1., 0, 0, false, 250., 250., 0.8, 0.2, 40., 0.1, 6000., 0.1, 0.5, 0.8, 1., 0., 1., 0.5, true,
-    false,
-  );
+  params.set(ParamValues {
+    input_level: 1.,
+    time_link: false,
+    feedback: 0.8,
+    wow_and_flutter: 0.2,
+    highpass_freq: 40.,
+    highpass_res: 0.1,
+    lowpass_res: 0.1,
+    reverb: 0.5,
+    decay: 0.8,
+    output_level: 1.,
+    limiter: true,
+    ..Default::default()
+  });
+
+  let mut left = [0.; 128];
+  let mut right = [0.; 128];
 
   loop {
-    let input = (generate_signal(), generate_signal());
-    space_echo.process(input, &mut params);
+    left.iter_mut().for_each(|x| *x = generate_signal());
+    right.iter_mut().for_each(|x| *x = generate_signal());
+    space_echo.process_block(&mut left, &mut right, &mut params);
   }
 }
@@ -7,3 +7,7 @@ pub fn generate_stereo_signal_stream(length: usize) -> Vec<(f32, f32)> {
     .map(|_| (generate_signal(), generate_signal()))
     .collect()
 }
+
+pub fn generate_signal_stream(length: usize) -> Vec<f32> {
+  (0..length).map(|_| generate_signal()).collect()
+}
@@ -2,19 +2,32 @@ use std::f32::consts::TAU;
 
 pub struct Average {
   z: f32,
+  filter_freq: f32,
   b1: f32,
 }
 
 impl Average {
   pub fn new(sample_rate: f32, filter_freq: f32) -> Self {
-    let t = sample_rate.recip() * -TAU;
-
     Self {
       z: 0.,
-      b1: (filter_freq * t).exp(),
+      filter_freq,
+      b1: Self::get_b1(sample_rate, filter_freq),
     }
   }
 
+  pub fn set_sample_rate(&mut self, sample_rate: f32) {
+    self.b1 = Self::get_b1(sample_rate, self.filter_freq);
+  }
+
+  fn get_b1(sample_rate: f32, filter_freq: f32) -> f32 {
+    let t = sample_rate.recip() * -TAU;
+    (filter_freq * t).exp()
+  }
+
+  pub fn reset(&mut self) {
+    self.z = 0.;
+  }
+
   pub fn process(&mut self, input: f32) -> f32 {
     let squared = input * input;
     let filtered = self.filter(squared);
@@ -1,14 +1,23 @@
+mod detector;
 mod slide;
-use crate::shared::float_ext::FloatExt;
-use slide::Slide;
+use crate::{params::DuckDetector, shared::float_ext::FloatExt};
+use {detector::Detector, slide::Slide};
 
-const ATTACK_TIME: f32 = 1.5;
-const RELEASE_TIME: f32 = 120.;
+const DEFAULT_ATTACK_TIME: f32 = 1.5;
+const DEFAULT_RELEASE_TIME: f32 = 120.;
 pub const MIN_DUCK_THRESHOLD: f32 = -60.;
 const MAX_DUCK_THRESHOLD: f32 = 0.;
 
 pub struct Duck {
   max_duck_threshold: f32,
+  sample_rate: f32,
+  detector_mode: DuckDetector,
+  detector: Detector,
+  min_gain: f32,
+  hold_time: f32,
+  hold_length: usize,
+  hold_counter: usize,
+  held_gain: f32,
   slide: Slide,
 }
 
@@ -16,31 +25,147 @@ impl Duck {
   pub fn new(sample_rate: f32) -> Self {
     Self {
       max_duck_threshold: MAX_DUCK_THRESHOLD.dbtoa(),
-      slide: Slide::new(sample_rate, RELEASE_TIME, ATTACK_TIME),
+      sample_rate,
+      detector_mode: DuckDetector::Peak,
+      detector: Detector::new(sample_rate),
+      min_gain: 0.,
+      hold_time: 0.,
+      hold_length: 0,
+      hold_counter: 0,
+      held_gain: 1.,
+      slide: Slide::new(sample_rate, DEFAULT_RELEASE_TIME, DEFAULT_ATTACK_TIME),
     }
   }
 
-  pub fn process(
-    &mut self,
-    input: (f32, f32),
-    side_chain_input: (f32, f32),
-    duck_threshold: f32,
-  ) -> (f32, f32) {
+  pub fn set_sample_rate(&mut self, sample_rate: f32) {
+    self.sample_rate = sample_rate;
+    self.hold_length = self.hold_time.mstosamps(sample_rate) as usize;
+    self.detector.set_sample_rate(sample_rate);
+    self.slide.set_sample_rate(sample_rate);
+  }
+
+  /// Sets the attack, release and hold time in milliseconds. The hold time delays the release.
+  pub fn set_envelope(&mut self, attack: f32, release: f32, hold: f32) {
+    self.slide.set_times(release, attack);
+    if hold != self.hold_time {
+      self.hold_time = hold;
+      self.hold_length = hold.mstosamps(self.sample_rate) as usize;
+    }
+  }
+
+  /// Sets the maximum attenuation in decibels.
+  pub fn set_depth(&mut self, depth: f32) {
+    self.min_gain = (-depth).dbtoa();
+  }
+
+  pub fn set_detector(&mut self, detector: DuckDetector, highpass_freq: f32) {
+    self.detector_mode = detector;
+    self.detector.set_highpass_freq(highpass_freq);
+  }
+
+  pub fn reset(&mut self) {
+    self.detector.reset();
+    self.hold_counter = 0;
+    self.held_gain = 1.;
+    self.slide.reset();
+  }
+
+  /// Returns the gain the duck applies, so it can attenuate any signal in the chain.
+  pub fn get_gain(&mut self, side_chain_input: (f32, f32), duck_threshold: f32) -> f32 {
     if duck_threshold == self.max_duck_threshold {
-      input
+      1.
     } else {
-      let peak = Self::detect_peak(side_chain_input);
-      let slide_input = if peak > duck_threshold {
-        duck_threshold / peak
+      let level = self.detector.process(side_chain_input, self.detector_mode);
+      let slide_input = if level > duck_threshold {
+        (duck_threshold / level).max(self.min_gain)
       } else {
         1.
       };
-      let duck_gain = self.slide.process(slide_input);
-      (input.0 * duck_gain, input.1 * duck_gain)
+      let held_gain = self.apply_hold(slide_input);
+      self.slide.process(held_gain)
+    }
+  }
+
+  /// Keeps the lowest gain for the hold time, before it's allowed to rise again.
+  fn apply_hold(&mut self, gain: f32) -> f32 {
+    if gain <= self.held_gain {
+      self.held_gain = gain;
+      self.hold_counter = self.hold_length;
+    } else if self.hold_counter > 0 {
+      self.hold_counter -= 1;
+    } else {
+      self.held_gain = gain;
     }
+    self.held_gain
   }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{Duck, DuckDetector};
+  use crate::shared::float_ext::FloatExt;
+  use std::f32::consts::TAU;
+
+  fn get_gains(duck: &mut Duck, side_chain_input: &[f32], duck_threshold: f32) -> Vec<f32> {
+    side_chain_input
+      .iter()
+      .map(|x| duck.get_gain((*x, *x), duck_threshold))
+      .collect()
+  }
+
+  #[test]
+  fn should_limit_the_attenuation_to_the_depth() {
+    let mut duck = Duck::new(1000.);
+    duck.set_depth(12.);
+
+    let gains = get_gains(&mut duck, &[1.; 200], (-60_f32).dbtoa());
+    assert!((gains[199] - (-12_f32).dbtoa()).abs() < 0.001);
+  }
+
+  #[test]
+  fn should_hold_the_attenuation_before_releasing() {
+    let mut duck = Duck::new(1000.);
+    duck.set_envelope(1., 10., 50.);
+    let side_chain_input: Vec<f32> = (0..200).map(|i| if i < 50 { 1. } else { 0. }).collect();
+
+    let gains = get_gains(&mut duck, &side_chain_input, (-60_f32).dbtoa());
+    assert!(gains[95] < 0.01);
+    assert!(gains[130] > 0.5);
+  }
+
+  #[test]
+  fn should_ignore_low_frequencies_with_the_highpass_filter() {
+    let get_average_gain = |highpass_freq: f32| {
+      let mut duck = Duck::new(8000.);
+      duck.set_detector(DuckDetector::Peak, highpass_freq);
+      let side_chain_input: Vec<f32> = (0..8000)
+        .map(|i| (i as f32 * TAU * 50. / 8000.).sin())
+        .collect();
+      get_gains(&mut duck, &side_chain_input, 0.1)[4000..]
+        .iter()
+        .sum::<f32>()
+        / 4000.
+    };
+
+    assert!(get_average_gain(0.) < 0.5);
+    assert!(get_average_gain(2000.) > 0.99);
+  }
+
+  #[test]
+  fn should_follow_the_average_level_with_the_rms_detector() {
+    let get_average_gain = |detector: DuckDetector| {
+      let mut duck = Duck::new(8000.);
+      duck.set_detector(detector, 0.);
+      let side_chain_input: Vec<f32> = (0..8000)
+        .map(|i| (i as f32 * TAU * 1000. / 8000.).sin())
+        .collect();
+      get_gains(&mut duck, &side_chain_input, 0.8)[4000..]
+        .iter()
+        .sum::<f32>()
+        / 4000.
+    };
 
-  fn detect_peak(input: (f32, f32)) -> f32 {
-    input.0.abs().max(input.1.abs())
+    assert!(get_average_gain(DuckDetector::Peak) < 0.95);
+    assert!(get_average_gain(DuckDetector::Rms) > 0.99);
   }
 }
@@ -0,0 +1,65 @@
+use {
+  crate::{average::Average, params::DuckDetector},
+  std::{
+    f32::consts::TAU,
+    simd::{f32x2, num::SimdFloat},
+  },
+};
+
+const RMS_FREQUENCY: f32 = 20.;
+
+/// Measures the level of the loudest channel of the sidechain after a one pole highpass filter.
+pub struct Detector {
+  sample_rate: f32,
+  highpass_freq: f32,
+  highpass_b1: f32,
+  highpass_z: f32x2,
+  average: Average,
+}
+
+impl Detector {
+  pub fn new(sample_rate: f32) -> Self {
+    Self {
+      sample_rate,
+      highpass_freq: 0.,
+      highpass_b1: 1.,
+      highpass_z: f32x2::splat(0.),
+      average: Average::new(sample_rate, RMS_FREQUENCY),
+    }
+  }
+
+  pub fn set_sample_rate(&mut self, sample_rate: f32) {
+    self.sample_rate = sample_rate;
+    self.highpass_b1 = Self::get_b1(sample_rate, self.highpass_freq);
+    self.average.set_sample_rate(sample_rate);
+  }
+
+  /// Sets the cutoff frequency of the highpass filter in hertz. Zero passes the full range.
+  pub fn set_highpass_freq(&mut self, freq: f32) {
+    if freq != self.highpass_freq {
+      self.highpass_freq = freq;
+      self.highpass_b1 = Self::get_b1(self.sample_rate, freq);
+    }
+  }
+
+  pub fn reset(&mut self) {
+    self.highpass_z = f32x2::splat(0.);
+    self.average.reset();
+  }
+
+  pub fn process(&mut self, input: (f32, f32), detector: DuckDetector) -> f32 {
+    let input = f32x2::from_array([input.0, input.1]);
+    self.highpass_z = input * f32x2::splat(1. - self.highpass_b1)
+      + self.highpass_z * f32x2::splat(self.highpass_b1);
+    let peak = (input - self.highpass_z).abs().reduce_max();
+
+    match detector {
+      DuckDetector::Peak => peak,
+      DuckDetector::Rms => self.average.process(peak),
+    }
+  }
+
+  fn get_b1(sample_rate: f32, freq: f32) -> f32 {
+    (-TAU * freq / sample_rate).exp()
+  }
+}
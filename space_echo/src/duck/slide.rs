@@ -1,6 +1,9 @@
 use crate::FloatExt;
 
 pub struct Slide {
+  sample_rate: f32,
+  slide_up_time: f32,
+  slide_down_time: f32,
   slide_up: f32,
   slide_down: f32,
   z: f32,
@@ -9,12 +12,34 @@ pub struct Slide {
 impl Slide {
   pub fn new(sample_rate: f32, slide_up: f32, slide_down: f32) -> Self {
     Self {
+      sample_rate,
+      slide_up_time: slide_up,
+      slide_down_time: slide_down,
       slide_up: slide_up.mstosamps(sample_rate).recip(),
       slide_down: slide_down.mstosamps(sample_rate).recip(),
       z: 1.,
     }
   }
 
+  pub fn set_sample_rate(&mut self, sample_rate: f32) {
+    self.sample_rate = sample_rate;
+    self.slide_up = self.slide_up_time.mstosamps(sample_rate).recip();
+    self.slide_down = self.slide_down_time.mstosamps(sample_rate).recip();
+  }
+
+  /// Sets the slide up and slide down times in milliseconds.
+  pub fn set_times(&mut self, slide_up: f32, slide_down: f32) {
+    if (slide_up, slide_down) != (self.slide_up_time, self.slide_down_time) {
+      self.slide_up_time = slide_up;
+      self.slide_down_time = slide_down;
+      self.set_sample_rate(self.sample_rate);
+    }
+  }
+
+  pub fn reset(&mut self) {
+    self.z = 1.;
+  }
+
   pub fn process(&mut self, input: f32) -> f32 {
     let difference = input - self.z;
     if difference.abs() <= f32::EPSILON {
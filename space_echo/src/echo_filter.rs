@@ -0,0 +1,79 @@
+use {
+  crate::tsk_filter_stereo::{FilterType, TSKFilterStereo},
+  std::simd::f32x2,
+};
+
+pub struct EchoFilter {
+  highpass_filter: TSKFilterStereo,
+  lowpass_filter: TSKFilterStereo,
+}
+
+impl EchoFilter {
+  pub fn new(sample_rate: f32) -> Self {
+    Self {
+      highpass_filter: TSKFilterStereo::new(sample_rate),
+      lowpass_filter: TSKFilterStereo::new(sample_rate),
+    }
+  }
+
+  pub fn set_sample_rate(&mut self, sample_rate: f32) {
+    self.highpass_filter.set_sample_rate(sample_rate);
+    self.lowpass_filter.set_sample_rate(sample_rate);
+  }
+
+  pub fn reset(&mut self) {
+    self.highpass_filter.reset();
+    self.lowpass_filter.reset();
+  }
+
+  pub fn process(
+    &mut self,
+    input: f32x2,
+    highpass_freq: f32,
+    highpass_res: f32,
+    lowpass_freq: f32,
+    lowpass_res: f32,
+    filter_fader: f32,
+  ) -> f32x2 {
+    match filter_fader {
+      0. => input,
+      1. => self.get_filter_output(
+        input,
+        highpass_freq,
+        highpass_res,
+        lowpass_freq,
+        lowpass_res,
+      ),
+      _ => {
+        let filter_out = self.get_filter_output(
+          input,
+          highpass_freq,
+          highpass_res,
+          lowpass_freq,
+          lowpass_res,
+        );
+        input + (filter_out - input) * f32x2::splat(filter_fader)
+      }
+    }
+  }
+
+  fn get_filter_output(
+    &mut self,
+    input: f32x2,
+    highpass_freq: f32,
+    highpass_res: f32,
+    lowpass_freq: f32,
+    lowpass_res: f32,
+  ) -> f32x2 {
+    let highpass_filter_out =
+      self
+        .highpass_filter
+        .process(input, highpass_freq, highpass_res, FilterType::Highpass);
+    self.lowpass_filter.process(
+      highpass_filter_out,
+      lowpass_freq,
+      lowpass_res,
+      FilterType::Lowpass,
+    )
+  }
+}
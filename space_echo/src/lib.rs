@@ -1,10 +1,16 @@
 #![feature(portable_simd)]
 mod average;
 mod duck;
+mod echo_filter;
 mod limiter;
+mod meters;
+mod oversampling;
 mod params;
+mod pitch_shifter;
 mod reverb;
 mod saturation;
+pub mod sync;
+mod tape_heads;
 mod tsk_filter_stereo;
 mod variable_delay_read;
 mod wow_and_flutter;
@@ -15,227 +21,681 @@ mod shared {
   pub mod mix;
   pub mod phasor;
   pub mod random_oscillator;
+  pub mod stereo_block;
 }
 
 use {
   average::Average,
   duck::Duck,
+  echo_filter::EchoFilter,
+  fastrand::Rng,
   limiter::Limiter,
-  params::Smoother,
+  meters::BlockLevels,
+  oversampling::{Oversampler, MAX_OVERSAMPLING_FACTOR, MAX_OVERSAMPLING_LATENCY},
+  params::SmoothedParams,
+  pitch_shifter::PitchShifter,
   saturation::Saturation,
   shared::{
-    delay_line::{DelayLine, Interpolation},
+    delay_line::DelayLine,
     float_ext::FloatExt,
     mix::Mix,
+    stereo_block::{load, StereoBlock},
   },
-  std::simd::{f32x2, num::SimdFloat},
-  tsk_filter_stereo::{FilterType, TSKFilterStereo},
-  variable_delay_read::VariableDelayRead,
-  wow_and_flutter::{WowAndFlutter, MAX_WOW_AND_FLUTTER_TIME_IN_SECS},
+  std::{
+    simd::{f32x2, f32x8, num::SimdFloat},
+    sync::Arc,
+  },
+  tape_heads::TapeHeads,
+  wow_and_flutter::{WowAndFlutter, MAX_WOW_AND_FLUTTER_TIME_IN_MS},
+};
+pub use {
+  meters::{Meters, MIN_METER_LEVEL},
+  params::{
+    ChannelMode, DuckDetector, DuckSource, DuckTarget, HeadMode, Oversampling, ParamValues, Params,
+    ReverbType, Routing, SaturationMode, TimeMode, MAX_BLOCK_SIZE,
+  },
+  reverb::{Reverb, DEFAULT_MAX_REVERB_SIZE, MAX_MODULATION_DEPTH_IN_MS, MAX_PREDELAY_IN_MS},
+  shared::delay_line::Interpolation,
 };
-pub use {params::Params, reverb::Reverb};
+
+const UNITY_GAIN: [f32; MAX_BLOCK_SIZE] = [1.; MAX_BLOCK_SIZE];
+
+/// The limits the buffers are allocated for, so the sample rate can be changed without allocating.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SpaceEchoConfig {
+  /// The longest delay time in milliseconds. Longer delay times are clamped.
+  pub max_delay_time: f32,
+  /// The highest sample rate that can be passed to [`SpaceEcho::set_sample_rate`].
+  pub max_sample_rate: f32,
+  /// The largest reverb size, relative to the default room. Larger sizes are clamped.
+  pub max_reverb_size: f32,
+}
+
+impl Default for SpaceEchoConfig {
+  fn default() -> Self {
+    Self {
+      max_delay_time: 2500.,
+      max_sample_rate: 192000.,
+      max_reverb_size: DEFAULT_MAX_REVERB_SIZE,
+    }
+  }
+}
 
 pub struct SpaceEcho {
+  config: SpaceEchoConfig,
+  sample_rate: f32,
+  seed: u64,
+  oversampling: Oversampling,
+  is_limiter_on: bool,
+  is_latency_constant: bool,
   delay_line_left: DelayLine,
   delay_line_right: DelayLine,
-  variable_delay_read_left: VariableDelayRead,
-  variable_delay_read_right: VariableDelayRead,
+  tape_heads: TapeHeads,
   wow_and_flutter: WowAndFlutter,
   average: Average,
-  highpass_filter: TSKFilterStereo,
-  lowpass_filter: TSKFilterStereo,
+  filter: EchoFilter,
+  feedback_filter: EchoFilter,
   reverb: Reverb,
   duck: Duck,
   limiter: Limiter,
+  saturation: Saturation,
+  pitch_shifter: PitchShifter,
+  filter_oversampler: Oversampler,
+  feedback_filter_oversampler: Oversampler,
+  saturation_oversampler: Oversampler,
+  limiter_oversampler: Oversampler,
+  latency_compensation: [DelayLine; 2],
+  meters: Arc<Meters>,
   mix: Mix,
 }
 
 impl SpaceEcho {
+  /// Creates a SpaceEcho with the default maximum delay time, that's allocated for the given sample rate only.
   pub fn new(sample_rate: f32) -> Self {
-    Self {
-      delay_line_left: DelayLine::new(
-        ((2.5 + MAX_WOW_AND_FLUTTER_TIME_IN_SECS) * sample_rate) as usize,
-        sample_rate,
-      ),
-      delay_line_right: DelayLine::new(
-        ((2.5 + MAX_WOW_AND_FLUTTER_TIME_IN_SECS) * sample_rate) as usize,
-        sample_rate,
-      ),
-      variable_delay_read_left: VariableDelayRead::new(sample_rate),
-      variable_delay_read_right: VariableDelayRead::new(sample_rate),
-      wow_and_flutter: WowAndFlutter::new(sample_rate),
-      average: Average::new(sample_rate, 20.),
-      highpass_filter: TSKFilterStereo::new(sample_rate),
-      lowpass_filter: TSKFilterStereo::new(sample_rate),
-      reverb: Reverb::new(sample_rate),
-      duck: Duck::new(sample_rate),
-      limiter: Limiter::new(sample_rate, 2., 10., 40., 0.966051),
+    Self::with_config(
+      sample_rate,
+      SpaceEchoConfig {
+        max_sample_rate: sample_rate,
+        ..Default::default()
+      },
+    )
+  }
+
+  /// The wow and flutter and the reverb modulation start from a random seed. Use [`SpaceEcho::seed`] to make the output reproducible.
+  pub fn with_config(sample_rate: f32, config: SpaceEchoConfig) -> Self {
+    let max_sample_rate = config.max_sample_rate;
+    let max_oversampled_rate = max_sample_rate * MAX_OVERSAMPLING_FACTOR as f32;
+    let delay_line_length =
+      (config.max_delay_time + MAX_WOW_AND_FLUTTER_TIME_IN_MS).mstosamps(max_sample_rate) as usize;
+    let limiter = Limiter::new(max_oversampled_rate, 2., 10., 40., 0.966051);
+    let latency_compensation_length = MAX_OVERSAMPLING_LATENCY + limiter.max_latency();
+
+    let mut space_echo = Self {
+      config,
+      sample_rate,
+      seed: fastrand::u64(..),
+      oversampling: Oversampling::default(),
+      is_limiter_on: false,
+      is_latency_constant: false,
+      delay_line_left: DelayLine::new(delay_line_length, max_sample_rate),
+      delay_line_right: DelayLine::new(delay_line_length, max_sample_rate),
+      tape_heads: TapeHeads::new(max_sample_rate),
+      wow_and_flutter: WowAndFlutter::new(max_sample_rate),
+      average: Average::new(max_sample_rate, 20.),
+      filter: EchoFilter::new(max_oversampled_rate),
+      feedback_filter: EchoFilter::new(max_oversampled_rate),
+      reverb: Reverb::with_max_size(max_sample_rate, config.max_reverb_size),
+      duck: Duck::new(max_sample_rate),
+      limiter,
+      saturation: Saturation::new(max_oversampled_rate),
+      pitch_shifter: PitchShifter::new(max_sample_rate),
+      filter_oversampler: Oversampler::new(),
+      feedback_filter_oversampler: Oversampler::new(),
+      saturation_oversampler: Oversampler::new(),
+      limiter_oversampler: Oversampler::new(),
+      latency_compensation: [
+        DelayLine::new(latency_compensation_length, max_sample_rate),
+        DelayLine::new(latency_compensation_length, max_sample_rate),
+      ],
+      meters: Arc::new(Meters::new()),
       mix: Mix::new(),
+    };
+    space_echo.set_sample_rate(sample_rate);
+    space_echo
+  }
+
+  pub fn config(&self) -> &SpaceEchoConfig {
+    &self.config
+  }
+
+  /// Re-derives all coefficients for the new sample rate and clears the state, without allocating.
+  ///
+  /// # Panics
+  ///
+  /// Panics if the sample rate exceeds the `max_sample_rate` of the [`SpaceEchoConfig`].
+  pub fn set_sample_rate(&mut self, sample_rate: f32) {
+    assert!(
+      sample_rate <= self.config.max_sample_rate,
+      "sample rate {} exceeds the max sample rate of {}",
+      sample_rate,
+      self.config.max_sample_rate
+    );
+
+    self.sample_rate = sample_rate;
+    self.delay_line_left.set_sample_rate(sample_rate);
+    self.delay_line_right.set_sample_rate(sample_rate);
+    self.tape_heads.set_sample_rate(sample_rate);
+    self.wow_and_flutter.set_sample_rate(sample_rate);
+    self.average.set_sample_rate(sample_rate);
+    self.reverb.set_sample_rate(sample_rate);
+    self.duck.set_sample_rate(sample_rate);
+    self.pitch_shifter.set_sample_rate(sample_rate);
+    self
+      .latency_compensation
+      .iter_mut()
+      .for_each(|delay_line| delay_line.set_sample_rate(sample_rate));
+    self.set_oversampled_sample_rate();
+    self.reset();
+  }
+
+  /// The latency in samples that's added by the lookahead and the oversampling of the limiter. <br />
+  /// The latency of the oversampled feedback loop is compensated by reading the tape earlier.
+  pub fn latency(&self) -> usize {
+    if self.is_limiter_on || self.is_latency_constant {
+      self.get_limiter_latency()
+    } else {
+      0
     }
   }
 
+  /// The shortest echo time in milliseconds the third head plays back on time with the given interpolation. <br />
+  /// The tape is read earlier by the latency of the feedback loop, so shorter times are clamped to it for each head.
+  /// The first and second head reach it at three and one and a half times the delay time.
+  pub fn min_time(&self, interpolation: Interpolation) -> f32 {
+    self.get_feedback_latency() + self.delay_line_left.min_time(interpolation)
+  }
+
+  /// Restarts the random modulation from the seed. Identical input and params then produce identical output. <br />
+  /// The seed is kept, so every reset restarts the same sequence.
+  pub fn seed(&mut self, seed: u64) {
+    self.seed = seed;
+    self.apply_seed();
+  }
+
+  /// The levels of the processor, which can be read from other threads while it's processing.
+  pub fn meters(&self) -> Arc<Meters> {
+    self.meters.clone()
+  }
+
+  /// Publishes the levels to existing meters, so readers keep working when the processor is recreated.
+  pub fn set_meters(&mut self, meters: Arc<Meters>) {
+    self.meters = meters;
+  }
+
+  /// The current gain reduction of the limiter in decibels.
+  pub fn limiter_gain_reduction(&self) -> f32 {
+    if self.is_limiter_on {
+      self.limiter.gain_reduction()
+    } else {
+      0.
+    }
+  }
+
+  /// Clears the tape, the filters, the reverb tail and the dynamics, without allocating.
+  pub fn reset(&mut self) {
+    self.delay_line_left.reset();
+    self.delay_line_right.reset();
+    self.tape_heads.reset();
+    self.average.reset();
+    self.filter.reset();
+    self.feedback_filter.reset();
+    self.reverb.reset();
+    self.duck.reset();
+    self.limiter.reset();
+    self.saturation.reset();
+    self.pitch_shifter.reset();
+    self.filter_oversampler.reset();
+    self.feedback_filter_oversampler.reset();
+    self.saturation_oversampler.reset();
+    self.limiter_oversampler.reset();
+    self
+      .latency_compensation
+      .iter_mut()
+      .for_each(|delay_line| delay_line.reset());
+    self.meters.reset();
+    self.apply_seed();
+  }
+
+  fn apply_seed(&mut self) {
+    let mut rng = Rng::with_seed(self.seed);
+    self.wow_and_flutter.seed(rng.u64(..));
+    self.reverb.seed(rng.u64(..));
+  }
+
   pub fn process(&mut self, input: (f32, f32), params: &mut Params) -> (f32, f32) {
+    let mut left = [input.0];
+    let mut right = [input.1];
+    self.process_block(&mut left, &mut right, params);
+    (left[0], right[0])
+  }
+
+  /// Processes the left and right channel in place. <br />
+  /// Both channels must have the same length.
+  pub fn process_block(&mut self, left: &mut [f32], right: &mut [f32], params: &mut Params) {
+    self.process_block_with_sidechain(left, right, None, params);
+  }
+
+  /// Processes the left and right channel in place, with the left and right channel of the sidechain as the input of the duck. <br />
+  /// The duck listens to the input instead if there's no sidechain, or if it's shorter than the block. <br />
+  /// Both channels must have the same length.
+  pub fn process_block_with_sidechain(
+    &mut self,
+    left: &mut [f32],
+    right: &mut [f32],
+    sidechain: Option<(&[f32], &[f32])>,
+    params: &mut Params,
+  ) {
+    assert_eq!(
+      left.len(),
+      right.len(),
+      "the left and right channel must have the same length"
+    );
+    left
+      .chunks_mut(MAX_BLOCK_SIZE)
+      .zip(right.chunks_mut(MAX_BLOCK_SIZE))
+      .enumerate()
+      .for_each(|(chunk, (left, right))| {
+        let range = chunk * MAX_BLOCK_SIZE..chunk * MAX_BLOCK_SIZE + left.len();
+        let sidechain = sidechain.and_then(|(sidechain_left, sidechain_right)| {
+          Some((
+            sidechain_left.get(range.clone())?,
+            sidechain_right.get(range)?,
+          ))
+        });
+        self.process_chunk(left, right, sidechain, params)
+      });
+  }
+
+  fn process_chunk(
+    &mut self,
+    left: &mut [f32],
+    right: &mut [f32],
+    sidechain: Option<(&[f32], &[f32])>,
+    params: &mut Params,
+  ) {
+    params.next_block(left.len());
+    self.set_oversampling(params.oversampling);
+    self.set_limiter(params.limiter, params.constant_latency);
     let Params {
       time_mode,
       channel_mode,
+      head_mode,
       lowpass_res,
       highpass_res,
-      duck_threshold,
-      limiter,
+      duck_source,
+      duck_target,
+      duck_attack,
+      duck_release,
+      duck_hold,
+      duck_depth,
+      duck_detector,
+      limiter_ceiling,
+      limiter_release,
+      limiter_link,
+      limiter_true_peak,
+      saturation_mode,
+      interpolation,
+      reverb_type,
+      routing,
       ..
     } = *params;
-    let input_level = params.input_level.next();
-    let feedback = params.feedback.next();
-    let flutter_gain = params.flutter_gain.next();
-    let highpass_freq = params.highpass_freq.next();
-    let lowpass_freq = params.lowpass_freq.next();
-    let reverb = params.reverb.next();
-    let decay = params.decay.next();
-    let stereo = params.stereo.next();
-    let output_level = params.output_level.next();
-    let mix = params.mix.next();
-    let filter_fader = params.filter_fader.next();
-    let (time_left, time_right) = params.get_time(time_mode);
-
-    let delay_input = self.get_delay_input(input, channel_mode, input_level);
-    let delay_output = self.read_from_delay_lines(time_left, time_right, time_mode, flutter_gain);
-
-    let average = self
-      .average
-      .process(Self::take_loudest_channel(delay_output));
-    let gain_compensation = Self::retrieve_gain_compensation(average, 0.4);
-
-    let filter_output = self.apply_filter(
-      delay_output,
-      highpass_freq,
-      highpass_res,
-      lowpass_freq,
-      lowpass_res,
-      filter_fader,
+    let smoothed_params = params.smoothed_params();
+    let SmoothedParams {
+      drive,
+      width,
+      bias,
+      shimmer_interval,
+      shimmer,
+      decay,
+      decay_low,
+      decay_high,
+      damping,
+      modulation_rate,
+      modulation_depth,
+      duck_highpass_freq,
+      ..
+    } = *smoothed_params;
+    self.saturation.set_hysteresis(drive, width, bias);
+    self.pitch_shifter.set_interval(shimmer_interval);
+    self.reverb.set_interpolation(interpolation);
+    self.reverb.set_reverb_type(reverb_type);
+    self.reverb.set_decay_multipliers(decay_low, decay_high);
+    self.reverb.set_damping(damping);
+    self
+      .reverb
+      .set_modulation(modulation_rate, modulation_depth);
+    self.duck.set_envelope(duck_attack, duck_release, duck_hold);
+    self.duck.set_depth(duck_depth);
+    self.duck.set_detector(duck_detector, duck_highpass_freq);
+    self.limiter.set_ceiling(limiter_ceiling.dbtoa());
+    self.limiter.set_release(limiter_release);
+    self.limiter.set_stereo_link(limiter_link);
+    self.limiter.set_true_peak(limiter_true_peak);
+    let feedback_latency = self.get_feedback_latency();
+    let block_size = left.len();
+    let mut wow_and_flutter_time = [0.; MAX_BLOCK_SIZE];
+    self.wow_and_flutter.process_block(
+      &smoothed_params.flutter_gain[..block_size],
+      &mut wow_and_flutter_time[..block_size],
     );
-    let feedback_matrix_output = self.apply_channel_mode(filter_output, channel_mode);
-    self.write_to_delay_lines(delay_input, feedback_matrix_output, feedback, average);
+    let block_duration = block_size as f32 / self.sample_rate;
+    let mut levels = BlockLevels::default();
 
-    let stereo_output =
-      self.apply_stereo_amount(filter_output, stereo) * f32x2::splat(gain_compensation);
+    let mut dry = StereoBlock::from_slices(left, right);
+    levels.input = dry.peak(block_size);
+    let mut dry_send = dry;
+    dry_send.map(block_size, |i, left, right| {
+      let input_level = load(&smoothed_params.input_level, i);
+      (left * input_level, right * input_level)
+    });
 
-    let reverb_output = self
-      .reverb
-      .process((stereo_output[0], stereo_output[1]), reverb, decay);
-    let ducking_output = self.duck.process(reverb_output, input, duck_threshold);
-    let space_echo_output = self.apply_gain(ducking_output, output_level);
-    let mix_output = self.mix.process(input, space_echo_output, mix);
-    self.limiter.process(mix_output, limiter)
+    let mut duck_gain = [1.; MAX_BLOCK_SIZE];
+    for (i, duck_gain) in duck_gain.iter_mut().enumerate().take(block_size) {
+      let duck_input = match (duck_source, sidechain) {
+        (DuckSource::Sidechain, Some((sidechain_left, sidechain_right))) => {
+          (sidechain_left[i], sidechain_right[i])
+        }
+        _ => (dry.left[i], dry.right[i]),
+      };
+      *duck_gain = self
+        .duck
+        .get_gain(duck_input, smoothed_params.duck_threshold[i]);
+    }
+    levels.duck_gain = duck_gain[..block_size].iter().fold(1., |min, x| x.min(min));
+    let get_duck_gain = |target: DuckTarget| {
+      if target == duck_target {
+        &duck_gain
+      } else {
+        &UNITY_GAIN
+      }
+    };
+    let reverb_return_gain = get_duck_gain(DuckTarget::Reverb);
+    let process_reverb = |reverb: &mut Reverb, i: usize, dry: f32x2, send: f32x2| {
+      reverb.set_size(smoothed_params.size[i]);
+      reverb.set_predelay(smoothed_params.predelay[i]);
+      reverb.set_freeze(smoothed_params.freeze[i]);
+      let output = reverb.process_send(
+        (dry[0], dry[1]),
+        (send[0], send[1]),
+        smoothed_params.reverb[i],
+        decay,
+        reverb_return_gain[i],
+      );
+      f32x2::from_array([output.0, output.1])
+    };
+
+    let mut echo_input = dry_send;
+    if routing == Routing::ReverbIntoEcho {
+      for i in 0..block_size {
+        echo_input.set(
+          i,
+          process_reverb(&mut self.reverb, i, dry_send.get(i), dry_send.get(i)),
+        );
+      }
+    }
+
+    let mut gain_compensation = [1.; MAX_BLOCK_SIZE];
+    let mut echo_output = StereoBlock::new();
+    for i in 0..block_size {
+      let filter_fader = smoothed_params.filter_fader[i];
+      let highpass_freq = smoothed_params.highpass_freq[i];
+      let lowpass_freq = smoothed_params.lowpass_freq[i];
+      let delay_input = self.get_delay_input(echo_input.get(i), channel_mode);
+      let delay_input = self.pitch_shifter.delay_dry(delay_input);
+      let heads_output = self.tape_heads.read(
+        (&self.delay_line_left, &self.delay_line_right),
+        (
+          smoothed_params.time_left[i].min(self.config.max_delay_time),
+          smoothed_params.time_right[i].min(self.config.max_delay_time),
+        ),
+        time_mode,
+        interpolation,
+        wow_and_flutter_time[i] - feedback_latency,
+        smoothed_params.head_gains[i],
+      );
+      let delay_output = heads_output.into_iter().sum();
+
+      let average = self
+        .average
+        .process(Self::take_loudest_channel(delay_output));
+      gain_compensation[i] = Self::retrieve_gain_compensation(average, 0.4);
+
+      let filter_output = self.filter_oversampler.process(delay_output, |x| {
+        self.filter.process(
+          x,
+          highpass_freq,
+          highpass_res,
+          lowpass_freq,
+          lowpass_res,
+          filter_fader,
+        )
+      });
+      echo_output.set(i, filter_output);
+      let feedback_output = if head_mode == HeadMode::SingleHead {
+        filter_output
+      } else {
+        self.feedback_filter_oversampler.process(
+          Self::mix_heads_feedback(heads_output, smoothed_params.head_feedback[i]),
+          |x| {
+            self.feedback_filter.process(
+              x,
+              highpass_freq,
+              highpass_res,
+              lowpass_freq,
+              lowpass_res,
+              filter_fader,
+            )
+          },
+        )
+      };
+      let feedback_matrix_output = self.apply_channel_mode(feedback_output, channel_mode);
+      let pitch_shifter_output = self.pitch_shifter.process(feedback_matrix_output, shimmer);
+      let feedback = smoothed_params.feedback[i] * get_duck_gain(DuckTarget::Feedback)[i];
+      self.write_to_delay_lines(
+        delay_input,
+        pitch_shifter_output,
+        feedback,
+        saturation_mode,
+        average,
+      );
+      levels.feedback = levels
+        .feedback
+        .max(Self::take_loudest_channel(pitch_shifter_output) * feedback);
+      levels.gain_compensation = levels.gain_compensation.min(gain_compensation[i]);
+    }
+
+    let echo_gain = get_duck_gain(DuckTarget::Echo);
+    echo_output.map(block_size, |i, left, right| {
+      let (left, right) = Self::apply_stereo_amount(left, right, load(&smoothed_params.stereo, i));
+      let gain = load(&gain_compensation, i) * load(echo_gain, i);
+      (left * gain, right * gain)
+    });
+
+    let mut wet = echo_output;
+    let send_dry = |wet: &mut StereoBlock, send: &StereoBlock| {
+      wet.map(block_size, |i, left, right| {
+        let dry_reverb_send = load(&smoothed_params.dry_reverb_send, i);
+        (
+          left + load(&send.left, i) * dry_reverb_send,
+          right + load(&send.right, i) * dry_reverb_send,
+        )
+      })
+    };
+    match routing {
+      Routing::EchoIntoReverb => {
+        send_dry(&mut wet, &dry_send);
+        for i in 0..block_size {
+          wet.set(
+            i,
+            process_reverb(&mut self.reverb, i, wet.get(i), wet.get(i)),
+          );
+        }
+      }
+      Routing::ReverbIntoEcho => send_dry(&mut wet, &echo_input),
+      Routing::Parallel => {
+        for i in 0..block_size {
+          wet.set(
+            i,
+            process_reverb(&mut self.reverb, i, echo_output.get(i), dry_send.get(i)),
+          );
+        }
+      }
+      Routing::ReverbOnDryOnly => {
+        for i in 0..block_size {
+          dry.set(
+            i,
+            process_reverb(&mut self.reverb, i, dry.get(i), dry.get(i)),
+          );
+        }
+      }
+    };
+
+    let wet_gain = get_duck_gain(DuckTarget::Wet);
+    wet.map(block_size, |i, left, right| {
+      let wet_gain = load(wet_gain, i);
+      let output_level = load(&smoothed_params.output_level, i);
+      (
+        left * wet_gain * output_level,
+        right * wet_gain * output_level,
+      )
+    });
+    self
+      .mix
+      .process_block(&mut dry, &wet, &smoothed_params.mix, block_size);
+
+    let mut output = StereoBlock::new();
+    for i in 0..block_size {
+      let (left, right) = self.apply_limiter((dry.left[i], dry.right[i]));
+      output.set(i, f32x2::from_array([left, right]));
+    }
+    levels.output = output.peak(block_size);
+    output.copy_to_slices(left, right);
+
+    self
+      .meters
+      .update(&levels, self.limiter_gain_reduction(), block_duration);
   }
 
-  fn apply_gain(&self, input: (f32, f32), gain: f32) -> (f32, f32) {
-    (input.0 * gain, input.1 * gain)
+  /// Runs the sample rate dependent parts of the oversampled sections at the oversampled rate.
+  fn set_oversampled_sample_rate(&mut self) {
+    let oversampled_rate = self.sample_rate * self.oversampling.factor() as f32;
+    self.filter.set_sample_rate(oversampled_rate);
+    self.feedback_filter.set_sample_rate(oversampled_rate);
+    self.saturation.set_sample_rate(oversampled_rate);
+    self.limiter.set_sample_rate(oversampled_rate);
   }
 
-  fn get_delay_input(&self, input: (f32, f32), channel_mode: i32, gain: f32) -> f32x2 {
-    let input = self.apply_gain(input, gain);
+  fn set_oversampling(&mut self, oversampling: Oversampling) {
+    if oversampling == self.oversampling {
+      return;
+    }
+    self.oversampling = oversampling;
+    self.filter_oversampler.set_oversampling(oversampling);
+    self
+      .feedback_filter_oversampler
+      .set_oversampling(oversampling);
+    self.saturation_oversampler.set_oversampling(oversampling);
+    self.limiter_oversampler.set_oversampling(oversampling);
+    self.set_oversampled_sample_rate();
+    self.filter.reset();
+    self.feedback_filter.reset();
+    self.saturation.reset();
+  }
 
-    match channel_mode {
-      1 => f32x2::from_array([(input.0 + input.1) * 0.5, 0.]),
-      _ => f32x2::from_array([input.0, input.1]),
+  /// Clears the lookahead of the part that starts running, so it doesn't play back what it held the last time it was used. <br />
+  /// With a constant latency the limiter and the latency compensation both keep running, so the limiter can be toggled without a gap.
+  fn set_limiter(&mut self, is_on: bool, is_latency_constant: bool) {
+    if (is_on || is_latency_constant) && !(self.is_limiter_on || self.is_latency_constant) {
+      self.limiter_oversampler.reset();
+      self.limiter.reset();
     }
+    if is_latency_constant && !self.is_latency_constant {
+      self
+        .latency_compensation
+        .iter_mut()
+        .for_each(|delay_line| delay_line.reset());
+    }
+    self.is_limiter_on = is_on;
+    self.is_latency_constant = is_latency_constant;
   }
 
-  fn read_from_delay_lines(
-    &mut self,
-    time_left: f32,
-    time_right: f32,
-    time_mode: i32,
-    flutter_gain: f32,
-  ) -> f32x2 {
-    let wow_and_flutter_time = if flutter_gain > 0. {
-      self.wow_and_flutter.process(flutter_gain)
-    } else {
-      0.
-    };
+  /// The latency in milliseconds of the oversampled filter and saturation and of the pitch shifter.
+  fn get_feedback_latency(&self) -> f32 {
+    ((self.filter_oversampler.latency() + self.saturation_oversampler.latency()) as f32)
+      .sampstoms(self.sample_rate)
+      + self.pitch_shifter.latency()
+  }
 
-    if time_mode == 0 {
-      let delay_out_left = self
-        .delay_line_left
-        .read(time_left + wow_and_flutter_time, Interpolation::Linear);
-      let delay_out_right = self
-        .delay_line_right
-        .read(time_right + wow_and_flutter_time, Interpolation::Linear);
+  fn get_limiter_latency(&self) -> usize {
+    self.limiter_oversampler.latency() + self.limiter.latency().div_ceil(self.oversampling.factor())
+  }
 
-      f32x2::from_array([delay_out_left, delay_out_right])
+  fn apply_limiter(&mut self, input: (f32, f32)) -> (f32, f32) {
+    let compensated_output = if self.is_latency_constant {
+      self.apply_latency_compensation(input)
     } else {
-      let delay_out_left = self.variable_delay_read_left.read(
-        &self.delay_line_left,
-        time_left,
-        wow_and_flutter_time,
-        Interpolation::Linear,
-      );
-      let delay_out_right = self.variable_delay_read_right.read(
-        &self.delay_line_right,
-        time_right,
-        wow_and_flutter_time,
-        Interpolation::Linear,
-      );
+      input
+    };
+    if !self.is_limiter_on && !self.is_latency_constant {
+      return compensated_output;
+    }
 
-      f32x2::from_array([delay_out_left, delay_out_right])
+    let limiter_output =
+      self
+        .limiter_oversampler
+        .process(f32x2::from_array([input.0, input.1]), |x| {
+          let (left, right) = self.limiter.process((x[0], x[1]), true);
+          f32x2::from_array([left, right])
+        });
+    if self.is_limiter_on {
+      (limiter_output[0], limiter_output[1])
+    } else {
+      compensated_output
     }
   }
 
-  fn apply_filter(
-    &mut self,
-    input: f32x2,
-    highpass_freq: f32,
-    highpass_res: f32,
-    lowpass_freq: f32,
-    lowpass_res: f32,
-    filter_fader: f32,
-  ) -> f32x2 {
-    match filter_fader {
-      0. => input,
-      1. => self.get_filter_output(
-        input,
-        highpass_freq,
-        highpass_res,
-        lowpass_freq,
-        lowpass_res,
-      ),
-      _ => {
-        let filter_out = self.get_filter_output(
-          input,
-          highpass_freq,
-          highpass_res,
-          lowpass_freq,
-          lowpass_res,
-        );
-        input + (filter_out - input) * f32x2::splat(filter_fader)
-      }
+  /// Delays the signal by the latency of the limiter, so it can replace the limiter while it's off.
+  fn apply_latency_compensation(&mut self, input: (f32, f32)) -> (f32, f32) {
+    let time = (self.get_limiter_latency() as f32).sampstoms(self.sample_rate);
+    let [left, right] = &mut self.latency_compensation;
+    let output = (
+      left.read(time, Interpolation::Step),
+      right.read(time, Interpolation::Step),
+    );
+    left.write(input.0);
+    right.write(input.1);
+    output
+  }
+
+  fn get_delay_input(&self, input: f32x2, channel_mode: ChannelMode) -> f32x2 {
+    match channel_mode {
+      ChannelMode::Stereo => input,
+      ChannelMode::PingPong => f32x2::from_array([input.reduce_sum() * 0.5, 0.]),
     }
   }
 
-  fn get_filter_output(
-    &mut self,
-    input: f32x2,
-    highpass_freq: f32,
-    highpass_res: f32,
-    lowpass_freq: f32,
-    lowpass_res: f32,
-  ) -> f32x2 {
-    let highpass_filter_out =
-      self
-        .highpass_filter
-        .process(input, highpass_freq, highpass_res, FilterType::Highpass);
-    self.lowpass_filter.process(
-      highpass_filter_out,
-      lowpass_freq,
-      lowpass_res,
-      FilterType::Lowpass,
-    )
+  fn mix_heads_feedback(heads_output: [f32x2; 3], head_feedback: [f32; 3]) -> f32x2 {
+    heads_output
+      .into_iter()
+      .zip(head_feedback)
+      .map(|(head_output, feedback)| head_output * f32x2::splat(feedback))
+      .sum()
   }
 
-  fn apply_channel_mode(&mut self, input: f32x2, channel_mode: i32) -> f32x2 {
+  fn apply_channel_mode(&mut self, input: f32x2, channel_mode: ChannelMode) -> f32x2 {
     match channel_mode {
-      1 => input.reverse(),
-      _ => input,
+      ChannelMode::Stereo => input,
+      ChannelMode::PingPong => input.reverse(),
     }
   }
 
@@ -244,19 +704,25 @@ impl SpaceEcho {
     dry_input: f32x2,
     feedback_input: f32x2,
     feedback: f32,
+    saturation_mode: SaturationMode,
     saturation_mix: f32,
   ) {
     let feedback_output = dry_input + feedback_input * f32x2::splat(feedback);
-    let saturation_output = Saturation::process(feedback_output, saturation_mix);
+    let saturation_output = self.saturation_oversampler.process(feedback_output, |x| {
+      self.saturation.process(x, saturation_mode, saturation_mix)
+    });
 
     self.delay_line_left.write(saturation_output[0]);
     self.delay_line_right.write(saturation_output[1]);
   }
 
-  fn apply_stereo_amount(&self, input: f32x2, stereo: f32) -> f32x2 {
-    let factor = (1. - stereo) * 0.5;
+  fn apply_stereo_amount(left: f32x8, right: f32x8, stereo: f32x8) -> (f32x8, f32x8) {
+    let factor = (f32x8::splat(1.) - stereo) * f32x8::splat(0.5);
 
-    input + (input - input.reverse()) * f32x2::splat(factor)
+    (
+      left + (left - right) * factor,
+      right + (right - left) * factor,
+    )
   }
 
   fn take_loudest_channel(input: f32x2) -> f32 {
@@ -271,3 +737,555 @@ impl SpaceEcho {
     }
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::{
+    DuckSource, DuckTarget, Interpolation, Oversampling, ParamValues, Params, Routing, SpaceEcho,
+    SpaceEchoConfig, MIN_METER_LEVEL,
+  };
+  use std::{f32::consts::TAU, ops::Range};
+
+  fn process_impulse(space_echo: &mut SpaceEcho, params: &mut Params, length: usize) -> Vec<f32> {
+    let mut left = vec![0.; length];
+    let mut right = vec![0.; length];
+    left[0] = 1.;
+    right[0] = 1.;
+    space_echo.process_block(&mut left, &mut right, params);
+    left
+  }
+
+  fn get_peak(output: &[f32]) -> f32 {
+    output.iter().fold(0_f32, |peak, x| peak.max(x.abs()))
+  }
+
+  fn get_rms(output: &[f32]) -> f32 {
+    (output.iter().map(|x| x * x).sum::<f32>() / output.len() as f32).sqrt()
+  }
+
+  /// Returns the index of the loudest sample in the range.
+  fn find_peak(output: &[f32], range: Range<usize>) -> Option<usize> {
+    range.max_by(|a, b| output[*a].abs().total_cmp(&output[*b].abs()))
+  }
+
+  #[test]
+  #[should_panic]
+  fn should_reject_channels_of_different_lengths() {
+    let mut space_echo = SpaceEcho::new(1000.);
+    let mut params = Params::new(1000.);
+    space_echo.process_block(&mut [0.; 64], &mut [0.; 48], &mut params);
+  }
+
+  #[test]
+  fn should_not_play_back_a_tail_after_reset() {
+    let mut space_echo = SpaceEcho::new(1000.);
+    let mut params = Params::new(1000.);
+    params.set(ParamValues {
+      time_left: 50.,
+      time_link: true,
+      feedback: 0.9,
+      reverb: 0.5,
+      mix: 1.,
+      ..Default::default()
+    });
+
+    let mut left = [0.; 100];
+    let mut right = [0.; 100];
+    left[0] = 1.;
+    right[0] = 1.;
+    space_echo.process_block(&mut left, &mut right, &mut params);
+    assert!(left.iter().any(|x| x.abs() > 0.01));
+
+    space_echo.reset();
+    params.reset();
+    let mut left = [0.; 200];
+    let mut right = [0.; 200];
+    space_echo.process_block(&mut left, &mut right, &mut params);
+    assert!(left.iter().chain(right.iter()).all(|x| *x == 0.));
+  }
+
+  #[test]
+  fn should_sound_the_same_after_changing_the_sample_rate() {
+    let values = ParamValues {
+      time_left: 20.,
+      feedback: 0.7,
+      mix: 1.,
+      ..Default::default()
+    };
+    let mut space_echo = SpaceEcho::with_config(
+      2000.,
+      SpaceEchoConfig {
+        max_sample_rate: 4000.,
+        ..Default::default()
+      },
+    );
+    let mut params = Params::new(2000.);
+    params.set(values);
+    process_impulse(&mut space_echo, &mut params, 500);
+
+    space_echo.set_sample_rate(1000.);
+    params.set_sample_rate(1000.);
+    params.reset();
+    let mut reference = SpaceEcho::new(1000.);
+    let mut reference_params = Params::new(1000.);
+    reference_params.set(values);
+
+    assert_eq!(
+      process_impulse(&mut space_echo, &mut params, 200),
+      process_impulse(&mut reference, &mut reference_params, 200)
+    );
+  }
+
+  #[test]
+  fn should_allow_delay_times_up_to_the_configured_maximum() {
+    let mut space_echo = SpaceEcho::with_config(
+      1000.,
+      SpaceEchoConfig {
+        max_delay_time: 10000.,
+        max_sample_rate: 1000.,
+        ..Default::default()
+      },
+    );
+    let mut params = Params::new(1000.);
+    params.set(ParamValues {
+      time_left: 8000.,
+      feedback: 0.,
+      mix: 1.,
+      ..Default::default()
+    });
+
+    let output = process_impulse(&mut space_echo, &mut params, 8100);
+    let first_echo = output.iter().position(|x| x.abs() > 0.1);
+    assert_eq!(first_echo.map(|i| i / 10), Some(800));
+  }
+
+  #[test]
+  fn should_keep_the_echo_time_when_oversampling() {
+    let get_echo_peaks = |oversampling: Oversampling| {
+      let mut space_echo = SpaceEcho::new(8000.);
+      let mut params = Params::new(8000.);
+      params.set(ParamValues {
+        time_left: 50.,
+        feedback: 0.5,
+        mix: 1.,
+        oversampling,
+        ..Default::default()
+      });
+
+      let output = process_impulse(&mut space_echo, &mut params, 1000);
+      [200..600, 600..1000].map(|range| find_peak(&output, range))
+    };
+
+    let echo_peaks = get_echo_peaks(Oversampling::X1);
+    assert_eq!(echo_peaks, [Some(400), Some(800)]);
+    assert_eq!(get_echo_peaks(Oversampling::X2), echo_peaks);
+    assert_eq!(get_echo_peaks(Oversampling::X4), echo_peaks);
+  }
+
+  #[test]
+  fn should_clamp_echo_times_below_the_min_time() {
+    [Oversampling::X1, Oversampling::X2, Oversampling::X4]
+      .into_iter()
+      .for_each(|oversampling| {
+        let mut space_echo = SpaceEcho::new(8000.);
+        let mut params = Params::new(8000.);
+        let get_echo_peak = |space_echo: &mut SpaceEcho, params: &mut Params, time: f32| {
+          params.set(ParamValues {
+            time_left: time,
+            feedback: 0.,
+            mix: 1.,
+            oversampling,
+            ..Default::default()
+          });
+          space_echo.reset();
+          params.reset();
+          let output = process_impulse(space_echo, params, 400);
+          find_peak(&output, 0..400)
+        };
+
+        get_echo_peak(&mut space_echo, &mut params, 100.);
+        let min_time = space_echo.min_time(Interpolation::Linear);
+        let min_echo_peak = Some((min_time * 8.).round() as usize);
+        assert_eq!(
+          get_echo_peak(&mut space_echo, &mut params, 1.),
+          min_echo_peak
+        );
+        assert_eq!(
+          get_echo_peak(&mut space_echo, &mut params, min_time + 5.),
+          min_echo_peak.map(|peak| peak + 40)
+        );
+      });
+  }
+
+  #[test]
+  fn should_keep_the_echo_time_with_shimmer() {
+    let get_echo_peaks = |time: f32| {
+      let mut space_echo = SpaceEcho::new(8000.);
+      let mut params = Params::new(8000.);
+      params.set(ParamValues {
+        time_left: time,
+        feedback: 0.5,
+        shimmer_interval: 0.,
+        shimmer: 1.,
+        mix: 1.,
+        ..Default::default()
+      });
+
+      let output = process_impulse(&mut space_echo, &mut params, 1400);
+      let length = (time * 8.) as usize;
+      [1, 2, 3].map(|repeat| {
+        find_peak(
+          &output,
+          length * repeat - length / 2..length * repeat + length / 2,
+        )
+      })
+    };
+
+    assert_eq!(get_echo_peaks(50.), [Some(400), Some(800), Some(1200)]);
+    // just above the min time, which is the latency of the shifter at this sample rate
+    assert_eq!(SpaceEcho::new(8000.).min_time(Interpolation::Linear), 20.);
+    assert_eq!(get_echo_peaks(21.), [Some(168), Some(336), Some(504)]);
+  }
+
+  #[test]
+  fn should_fade_in_the_shimmer_without_a_step() {
+    let mut space_echo = SpaceEcho::new(8000.);
+    let mut params = Params::new(8000.);
+    params.set(ParamValues {
+      time_left: 50.,
+      feedback: 0.5,
+      shimmer_interval: 0.,
+      mix: 1.,
+      ..Default::default()
+    });
+    let input: Vec<f32> = (0..4000)
+      .map(|i| (i as f32 * TAU * 110. / 8000.).sin() * 0.5)
+      .collect();
+
+    let mut output = Vec::new();
+    for (i, block) in input.chunks(32).enumerate() {
+      if i == 40 {
+        params.set_shimmer(1.);
+      }
+      let mut left = block.to_vec();
+      let mut right = block.to_vec();
+      space_echo.process_block(&mut left, &mut right, &mut params);
+      output.extend(left);
+    }
+    // a 110 Hz sine at 0.5 moves by at most 0.043 per sample, so the sum of the repeats stays well below 0.2
+    assert!(output
+      .windows(2)
+      .all(|pair| (pair[1] - pair[0]).abs() < 0.2));
+  }
+
+  #[test]
+  fn should_stay_stable_with_shimmer_at_full_feedback() {
+    let mut space_echo = SpaceEcho::new(8000.);
+    let mut params = Params::new(8000.);
+    params.set(ParamValues {
+      time_left: 100.,
+      feedback: 1.5,
+      shimmer_interval: 12.,
+      shimmer: 1.,
+      mix: 1.,
+      ..Default::default()
+    });
+
+    let mut left: Vec<f32> = (0..40000)
+      .map(|i| {
+        if i < 800 {
+          fastrand::f32() * 2. - 1.
+        } else {
+          0.
+        }
+      })
+      .collect();
+    let mut right = left.clone();
+    space_echo.process_block(&mut left, &mut right, &mut params);
+    let peak = get_peak(&left[32000..]);
+    assert!(peak.is_finite() && peak < 2.);
+  }
+
+  #[test]
+  fn should_stay_stable_when_holding_and_freezing() {
+    let mut space_echo = SpaceEcho::new(8000.);
+    let mut params = Params::new(8000.);
+    let values = ParamValues {
+      time_left: 100.,
+      feedback: 0.9,
+      reverb: 1.,
+      mix: 1.,
+      ..Default::default()
+    };
+    params.set(values);
+    let mut left: Vec<f32> = (0..8000).map(|_| fastrand::f32() * 2. - 1.).collect();
+    let mut right = left.clone();
+    space_echo.process_block(&mut left, &mut right, &mut params);
+
+    params.set(ParamValues {
+      hold: true,
+      freeze: true,
+      ..values
+    });
+    let mut left = vec![0.; 80000];
+    let mut right = left.clone();
+    space_echo.process_block(&mut left, &mut right, &mut params);
+    let start = get_peak(&left[8000..16000]);
+    let end = get_peak(&left[72000..]);
+    assert!(end.is_finite() && end < start * 1.5);
+    assert!(end > start * 0.25);
+  }
+
+  #[test]
+  fn should_feed_the_reverb_into_the_echo() {
+    let get_output = |routing: Routing| {
+      let mut space_echo = SpaceEcho::new(8000.);
+      let mut params = Params::new(8000.);
+      params.set(ParamValues {
+        time_left: 100.,
+        feedback: 0.,
+        reverb: 0.5,
+        routing,
+        mix: 1.,
+        ..Default::default()
+      });
+      process_impulse(&mut space_echo, &mut params, 4000)
+    };
+
+    let reverb_into_echo = get_output(Routing::ReverbIntoEcho);
+    assert!(reverb_into_echo[..790].iter().all(|x| *x == 0.));
+    assert!(reverb_into_echo[1600..].iter().any(|x| x.abs() > 0.001));
+    let parallel = get_output(Routing::Parallel);
+    assert!(parallel[..790].iter().any(|x| x.abs() > 0.001));
+  }
+
+  #[test]
+  fn should_keep_the_echo_dry_in_reverb_on_dry_only() {
+    let get_output = |routing: Routing, reverb: f32, mix: f32| {
+      let mut space_echo = SpaceEcho::new(8000.);
+      let mut params = Params::new(8000.);
+      params.set(ParamValues {
+        time_left: 100.,
+        reverb,
+        routing,
+        mix,
+        ..Default::default()
+      });
+      process_impulse(&mut space_echo, &mut params, 4000)
+    };
+
+    assert_eq!(
+      get_output(Routing::ReverbOnDryOnly, 1., 1.),
+      get_output(Routing::EchoIntoReverb, 0., 1.)
+    );
+    let dry_output = get_output(Routing::ReverbOnDryOnly, 1., 0.);
+    assert!(dry_output[1..].iter().any(|x| x.abs() > 0.001));
+  }
+
+  #[test]
+  fn should_duck_by_the_sidechain() {
+    let get_output = |duck_source: DuckSource, sidechain_level: Option<f32>| {
+      let mut space_echo = SpaceEcho::new(8000.);
+      let mut params = Params::new(8000.);
+      params.set(ParamValues {
+        time_left: 50.,
+        duck: 1.,
+        duck_source,
+        mix: 1.,
+        ..Default::default()
+      });
+      let mut left: Vec<f32> = (0..8000)
+        .map(|i| (i as f32 * TAU * 200. / 8000.).sin() * 0.5)
+        .collect();
+      let mut right = left.clone();
+      let sidechain = sidechain_level.map(|level| {
+        (0..8000)
+          .map(|i| (i as f32 * TAU * 100. / 8000.).sin() * level)
+          .collect::<Vec<f32>>()
+      });
+      space_echo.process_block_with_sidechain(
+        &mut left,
+        &mut right,
+        sidechain.as_deref().map(|sidechain| (sidechain, sidechain)),
+        &mut params,
+      );
+      left
+    };
+    let ducked = get_rms(&get_output(DuckSource::Sidechain, Some(1.))[4000..]);
+    let not_ducked = get_rms(&get_output(DuckSource::Sidechain, Some(0.))[4000..]);
+    assert!(ducked < not_ducked * 0.1);
+    assert_eq!(
+      get_output(DuckSource::Sidechain, None),
+      get_output(DuckSource::Input, None)
+    );
+  }
+
+  #[test]
+  fn should_delay_the_dry_signal_by_the_reported_latency() {
+    [Oversampling::X1, Oversampling::X2, Oversampling::X4]
+      .into_iter()
+      .for_each(|oversampling| {
+        [(true, false), (true, true), (false, true)]
+          .into_iter()
+          .for_each(|(limiter, constant_latency)| {
+            let mut space_echo = SpaceEcho::new(48000.);
+            let mut params = Params::new(48000.);
+            params.set(ParamValues {
+              mix: 0.,
+              limiter,
+              limiter_true_peak: true,
+              constant_latency,
+              oversampling,
+              ..Default::default()
+            });
+            let impulse_response = process_impulse(&mut space_echo, &mut params, 512);
+            assert_eq!(
+              find_peak(&impulse_response, 0..impulse_response.len()),
+              Some(space_echo.latency())
+            );
+          });
+      });
+  }
+
+  #[test]
+  fn should_only_report_latency_while_the_limiter_is_on() {
+    let mut space_echo = SpaceEcho::new(48000.);
+    let mut params = Params::new(48000.);
+    process_impulse(&mut space_echo, &mut params, 64);
+    assert_eq!(space_echo.latency(), 0);
+
+    params.set_limiter(true);
+    process_impulse(&mut space_echo, &mut params, 64);
+    assert_eq!(space_echo.latency(), 107);
+  }
+
+  #[test]
+  fn should_toggle_the_limiter_without_a_gap_at_a_constant_latency() {
+    let mut space_echo = SpaceEcho::new(48000.);
+    let mut params = Params::new(48000.);
+    params.set(ParamValues {
+      mix: 0.,
+      constant_latency: true,
+      ..Default::default()
+    });
+    let input: Vec<f32> = (0..4800)
+      .map(|i| (i as f32 * TAU * 100. / 48000.).sin() * 0.5)
+      .collect();
+
+    let mut output = Vec::new();
+    for (i, block) in input.chunks(480).enumerate() {
+      params.set_limiter(i % 2 == 1);
+      let mut left = block.to_vec();
+      let mut right = block.to_vec();
+      space_echo.process_block(&mut left, &mut right, &mut params);
+      output.extend(left);
+    }
+    let latency = space_echo.latency();
+    assert!(output[latency..]
+      .iter()
+      .zip(&input)
+      .all(|(output, input)| (output - input).abs() < 0.001));
+  }
+
+  #[test]
+  fn should_produce_identical_output_with_the_same_seed() {
+    let input: Vec<f32> = (0..8000)
+      .map(|i| (i as f32 * TAU * 220. / 8000.).sin())
+      .collect();
+    let process = |space_echo: &mut SpaceEcho| {
+      let mut params = Params::new(8000.);
+      params.set(ParamValues {
+        wow_and_flutter: 1.,
+        reverb: 0.5,
+        modulation_depth: 1.,
+        ..Default::default()
+      });
+      let mut left = input.clone();
+      let mut right = input.clone();
+      space_echo.process_block(&mut left, &mut right, &mut params);
+      left
+    };
+    let mut space_echo = SpaceEcho::new(8000.);
+    space_echo.seed(42);
+    let output = process(&mut space_echo);
+
+    let mut other_space_echo = SpaceEcho::new(8000.);
+    other_space_echo.seed(42);
+    assert_eq!(process(&mut other_space_echo), output);
+
+    space_echo.reset();
+    assert_eq!(process(&mut space_echo), output);
+
+    other_space_echo.seed(7);
+    other_space_echo.reset();
+    assert_ne!(process(&mut other_space_echo), output);
+  }
+
+  #[test]
+  fn should_publish_the_levels_to_the_meters() {
+    let mut space_echo = SpaceEcho::new(8000.);
+    let mut params = Params::new(8000.);
+    params.set(ParamValues {
+      time_left: 50.,
+      feedback: 0.9,
+      duck: 1.,
+      limiter: true,
+      limiter_ceiling: -12.,
+      ..Default::default()
+    });
+    let meters = space_echo.meters();
+    let mut left: Vec<f32> = (0..8000)
+      .map(|i| (i as f32 * TAU * 200. / 8000.).sin() * 0.5)
+      .collect();
+    let mut right = vec![0.; 8000];
+    space_echo.process_block(&mut left, &mut right, &mut params);
+
+    assert!((meters.input().0 + 6.0206).abs() < 0.01);
+    assert_eq!(meters.input().1, MIN_METER_LEVEL);
+    assert!(meters.output().0 < -11.9);
+    assert!(meters.feedback() > MIN_METER_LEVEL);
+    assert!(meters.duck_gain_reduction() > 6.);
+    assert!(meters.limiter_gain_reduction() > 0.);
+
+    space_echo.reset();
+    assert_eq!(meters.input(), (MIN_METER_LEVEL, MIN_METER_LEVEL));
+  }
+
+  #[test]
+  fn should_only_duck_the_repeats_in_the_feedback_path() {
+    let get_output = |duck_target: DuckTarget, duck: f32| {
+      let mut space_echo = SpaceEcho::new(8000.);
+      let mut params = Params::new(8000.);
+      params.set(ParamValues {
+        time_left: 50.,
+        time_link: true,
+        feedback: 0.7,
+        duck,
+        duck_source: DuckSource::Sidechain,
+        duck_target,
+        mix: 1.,
+        ..Default::default()
+      });
+      let mut left = vec![0.; 2000];
+      let mut right = vec![0.; 2000];
+      left[0] = 1.;
+      right[0] = 1.;
+      let sidechain: Vec<f32> = (0..2000)
+        .map(|i| (i as f32 * TAU * 100. / 8000.).sin())
+        .collect();
+      space_echo.process_block_with_sidechain(
+        &mut left,
+        &mut right,
+        Some((&sidechain, &sidechain)),
+        &mut params,
+      );
+      left
+    };
+    let ducked_feedback = get_output(DuckTarget::Feedback, 1.);
+    let not_ducked = get_output(DuckTarget::Feedback, 0.);
+    let ducked_wet = get_output(DuckTarget::Wet, 1.);
+    assert!(get_peak(&ducked_feedback[..700]) > 0.1);
+    assert!(get_peak(&ducked_feedback[700..]) < get_peak(&not_ducked[700..]) * 0.01);
+    assert!(get_peak(&ducked_wet[..700]) < 0.01);
+  }
+}
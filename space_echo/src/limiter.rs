@@ -1,13 +1,24 @@
 mod moving_min;
 mod ramp_slide;
-use {moving_min::MovingMin, ramp_slide::RampSlide};
+mod true_peak;
+use {
+  crate::shared::float_ext::FloatExt, moving_min::MovingMin, ramp_slide::RampSlide,
+  true_peak::TruePeak,
+};
 
 pub struct Limiter {
   buffer: Vec<(f32, f32)>,
+  buffer_length: usize,
   buffer_index: usize,
-  slide: RampSlide,
-  limit: f32,
-  moving_min: MovingMin,
+  sample_rate: f32,
+  attack_time: f32,
+  slide: [RampSlide; 2],
+  ceiling: f32,
+  stereo_link: f32,
+  moving_min: [MovingMin; 2],
+  gain: (f32, f32),
+  true_peak: TruePeak,
+  is_true_peak_on: bool,
 }
 
 impl Limiter {
@@ -16,42 +27,151 @@ impl Limiter {
     attack_time: f32,
     hold_time: f32,
     release_time: f32,
-    limit: f32,
+    ceiling: f32,
   ) -> Self {
-    let buffer_length = (attack_time * 0.001 * sample_rate) as usize;
+    let buffer_length = (attack_time * 0.001 * sample_rate) as usize + TruePeak::LATENCY;
 
     Self {
       buffer: vec![(0., 0.); buffer_length],
+      buffer_length,
       buffer_index: 0,
-      slide: RampSlide::new(sample_rate, release_time, attack_time),
-      limit,
-      moving_min: MovingMin::new(sample_rate, attack_time, hold_time, limit),
+      sample_rate,
+      attack_time,
+      slide: [
+        RampSlide::new(sample_rate, release_time, attack_time),
+        RampSlide::new(sample_rate, release_time, attack_time),
+      ],
+      ceiling,
+      stereo_link: 1.,
+      moving_min: [
+        MovingMin::new(sample_rate, attack_time, hold_time, 1.),
+        MovingMin::new(sample_rate, attack_time, hold_time, 1.),
+      ],
+      gain: (1., 1.),
+      true_peak: TruePeak::new(),
+      is_true_peak_on: false,
     }
   }
 
+  /// The lookahead buffer isn't resized, so the sample rate can't exceed the sample rate the limiter was created with.
+  pub fn set_sample_rate(&mut self, sample_rate: f32) {
+    self.sample_rate = sample_rate;
+    self.set_buffer_length();
+    self
+      .slide
+      .iter_mut()
+      .for_each(|slide| slide.set_sample_rate(sample_rate));
+    self
+      .moving_min
+      .iter_mut()
+      .for_each(|moving_min| moving_min.set_sample_rate(sample_rate));
+    self.reset();
+  }
+
+  /// Sets the maximum output level as a linear amplitude value.
+  pub fn set_ceiling(&mut self, ceiling: f32) {
+    self.ceiling = ceiling;
+  }
+
+  /// Sets the time in milliseconds it takes the gain to recover.
+  pub fn set_release(&mut self, release_time: f32) {
+    self
+      .slide
+      .iter_mut()
+      .for_each(|slide| slide.set_slide_up(release_time));
+  }
+
+  /// Sets how much the channels share their gain reduction. At 1 both channels are reduced by the loudest channel, at 0 each channel is limited on its own.
+  pub fn set_stereo_link(&mut self, stereo_link: f32) {
+    self.stereo_link = stereo_link;
+  }
+
+  /// Detects the peaks between the samples. The lookahead always includes the delay of the true peak estimate,
+  /// so it can be switched without changing the latency or interrupting the signal.
+  pub fn set_true_peak(&mut self, is_on: bool) {
+    self.is_true_peak_on = is_on;
+  }
+
+  /// The delay of the lookahead buffer in samples. The oldest sample is read after the newest is written.
+  pub fn latency(&self) -> usize {
+    self.buffer_length - 1
+  }
+
+  /// The longest lookahead the buffer is allocated for, in samples at the sample rate the limiter was created with.
+  pub fn max_latency(&self) -> usize {
+    self.buffer.len()
+  }
+
+  pub fn reset(&mut self) {
+    self.buffer.fill((0., 0.));
+    self.buffer_index = 0;
+    self.slide.iter_mut().for_each(|slide| slide.reset());
+    self
+      .moving_min
+      .iter_mut()
+      .for_each(|moving_min| moving_min.reset());
+    self.gain = (1., 1.);
+    self.true_peak.reset();
+  }
+
   pub fn process(&mut self, input: (f32, f32), is_on: bool) -> (f32, f32) {
     if is_on {
-      let limiter_gain = self.get_limiter_gain(input);
+      self.gain = self.get_limiter_gain(input);
       self.write_to_buffer(input);
       let delay_output = self.read_from_buffer();
 
-      (delay_output.0 * limiter_gain, delay_output.1 * limiter_gain)
+      (delay_output.0 * self.gain.0, delay_output.1 * self.gain.1)
     } else {
+      self.gain = (1., 1.);
       input
     }
   }
 
-  fn get_limiter_gain(&mut self, input: (f32, f32)) -> f32 {
-    let gain_reduction = self.get_gain_reduction(input);
-    let moving_min = self.moving_min.process(gain_reduction);
-    let limiter_gain = self.slide.process(moving_min);
-    limiter_gain
+  /// Returns the current gain reduction of the loudest channel in decibels.
+  pub fn gain_reduction(&self) -> f32 {
+    -self.gain.0.min(self.gain.1).atodb()
+  }
+
+  fn get_limiter_gain(&mut self, input: (f32, f32)) -> (f32, f32) {
+    let (left, right) = self.get_levels(input);
+    let gain_reduction = [
+      self.get_gain_reduction(left),
+      self.get_gain_reduction(right),
+    ];
+    let [left_gain, right_gain] = [0, 1].map(|i| {
+      let moving_min = self.moving_min[i].process(gain_reduction[i]);
+      self.slide[i].process(moving_min)
+    });
+    (left_gain, right_gain)
+  }
+
+  /// Moves the level of each channel towards the level of the loudest channel by the stereo link amount. <br />
+  /// Without true peak detection the samples are read from the buffer, delayed as much as the true peak estimate.
+  /// The estimate keeps running, so it's up to date when it's switched on.
+  fn get_levels(&mut self, input: (f32, f32)) -> (f32, f32) {
+    let true_peak = self.true_peak.process(input);
+    let (left, right) = if self.is_true_peak_on {
+      true_peak
+    } else {
+      let delayed_input =
+        self.buffer[self.wrap(self.buffer_index + self.buffer_length - TruePeak::LATENCY)];
+      (delayed_input.0.abs(), delayed_input.1.abs())
+    };
+    let loudest = left.max(right);
+    (
+      left + (loudest - left) * self.stereo_link,
+      right + (loudest - right) * self.stereo_link,
+    )
+  }
+
+  fn set_buffer_length(&mut self) {
+    let attack_length = (self.attack_time * 0.001 * self.sample_rate) as usize;
+    self.buffer_length = (attack_length + TruePeak::LATENCY).min(self.buffer.len());
   }
 
   fn wrap(&self, buffer_index: usize) -> usize {
-    let buffer_len = self.buffer.len();
-    if buffer_index >= buffer_len {
-      buffer_index - buffer_len
+    if buffer_index >= self.buffer_length {
+      buffer_index - self.buffer_length
     } else {
       buffer_index
     }
@@ -61,10 +181,9 @@ impl Limiter {
     self.buffer[self.buffer_index]
   }
 
-  fn get_gain_reduction(&self, input: (f32, f32)) -> f32 {
-    let gain = input.0.abs().max(input.1.abs());
-    if gain > self.limit {
-      self.limit * gain.recip()
+  fn get_gain_reduction(&self, level: f32) -> f32 {
+    if level > self.ceiling {
+      self.ceiling * level.recip()
     } else {
       1.
     }
@@ -75,3 +194,123 @@ impl Limiter {
     self.buffer_index = self.wrap(self.buffer_index + 1);
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::{Limiter, TruePeak};
+  use std::f32::consts::{FRAC_PI_2, FRAC_PI_4};
+
+  fn process_burst(limiter: &mut Limiter, level: (f32, f32)) -> Vec<(f32, f32)> {
+    (0..200)
+      .map(|i| limiter.process(if i < 100 { level } else { (0., 0.) }, true))
+      .collect()
+  }
+
+  #[test]
+  fn should_not_exceed_the_ceiling() {
+    let mut limiter = Limiter::new(1000., 4., 0., 40., 1.);
+    limiter.set_ceiling(0.5);
+
+    let output = process_burst(&mut limiter, (2., 1.5));
+    assert!(output
+      .iter()
+      .all(|(left, right)| left.abs() <= 0.5 + f32::EPSILON && right.abs() <= 0.5 + f32::EPSILON));
+  }
+
+  #[test]
+  fn should_report_the_gain_reduction_in_decibels() {
+    let mut limiter = Limiter::new(1000., 4., 0., 40., 0.5);
+
+    for _ in 0..50 {
+      limiter.process((2., 2.), true);
+    }
+    assert!((limiter.gain_reduction() - 12.0412).abs() < 0.001);
+  }
+
+  #[test]
+  fn should_leave_the_quiet_channel_untouched_when_unlinked() {
+    let get_output = |stereo_link: f32| {
+      let mut limiter = Limiter::new(1000., 4., 0., 40., 1.);
+      limiter.set_stereo_link(stereo_link);
+      process_burst(&mut limiter, (2., 0.5))
+    };
+
+    assert_eq!(get_output(0.)[50].1, 0.5);
+    assert_eq!(get_output(1.)[50].1, 0.25);
+  }
+
+  #[test]
+  fn should_reduce_the_gain_by_the_inter_sample_peaks() {
+    let get_gain_reduction = |is_true_peak_on: bool| {
+      let mut limiter = Limiter::new(48000., 2., 10., 40., 0.8);
+      limiter.set_true_peak(is_true_peak_on);
+      // the samples of this sine are at 0.707, but it peaks at 1 between them
+      for i in 0..4800 {
+        let x = (i as f32 * FRAC_PI_2 + FRAC_PI_4).sin();
+        limiter.process((x, x), true);
+      }
+      limiter.gain_reduction()
+    };
+
+    assert_eq!(get_gain_reduction(false), 0.);
+    assert!((get_gain_reduction(true) - 1.9382).abs() < 0.2);
+  }
+
+  #[test]
+  fn should_keep_the_inter_sample_peaks_below_the_ceiling() {
+    let mut limiter = Limiter::new(48000., 2., 10., 40., 0.5);
+    limiter.set_true_peak(true);
+    let mut true_peak = TruePeak::new();
+    // a burst of a sine that peaks between the samples
+    let input: Vec<f32> = (0..4800)
+      .map(|i| {
+        if (1000..3000).contains(&i) {
+          (i as f32 * FRAC_PI_2 + FRAC_PI_4).sin()
+        } else {
+          0.
+        }
+      })
+      .collect();
+
+    let output_peak = input
+      .iter()
+      .map(|x| {
+        let output = limiter.process((*x, *x), true);
+        true_peak.process(output).0
+      })
+      .fold(0_f32, f32::max);
+    assert!(output_peak < 0.5 * 1.02);
+    assert_eq!(limiter.latency(), 95 + TruePeak::LATENCY);
+  }
+
+  #[test]
+  fn should_switch_true_peak_detection_without_interrupting_the_signal() {
+    let mut limiter = Limiter::new(48000., 2., 10., 40., 1.);
+    let latency = limiter.latency();
+    let input: Vec<f32> = (0..4800).map(|i| (i as f32 * 0.05).sin() * 0.5).collect();
+
+    let output: Vec<f32> = input
+      .iter()
+      .enumerate()
+      .map(|(i, x)| {
+        if i % 1200 == 600 {
+          limiter.set_true_peak(i % 2400 == 600);
+        }
+        limiter.process((*x, *x), true).0
+      })
+      .collect();
+    assert_eq!(limiter.latency(), latency);
+    assert!(output[latency..]
+      .iter()
+      .zip(&input)
+      .all(|(output, input)| output == input));
+  }
+
+  #[test]
+  fn should_report_no_gain_reduction_below_the_ceiling() {
+    let mut limiter = Limiter::new(1000., 4., 0., 40., 1.);
+
+    process_burst(&mut limiter, (0.5, 0.5));
+    assert_eq!(limiter.gain_reduction(), 0.);
+  }
+}
@@ -3,24 +3,43 @@ pub struct MovingMin {
   next_min: f32,
   next_hold_length: u32,
   hold_index: u32,
+  hold_time: f32,
   hold_length: u32,
   limit: f32,
 }
 
 impl MovingMin {
   pub fn new(sample_rate: f32, attack_time: f32, hold_time: f32, limit: f32) -> Self {
-    let hold_length = ((attack_time + hold_time) * 0.001 * sample_rate - 1.0) as u32;
+    let hold_time = attack_time + hold_time;
+    let hold_length = Self::get_hold_length(sample_rate, hold_time);
 
     Self {
       current_min: limit,
       next_min: limit,
       next_hold_length: 0,
       hold_index: hold_length,
+      hold_time,
       hold_length,
       limit,
     }
   }
 
+  pub fn set_sample_rate(&mut self, sample_rate: f32) {
+    self.hold_length = Self::get_hold_length(sample_rate, self.hold_time);
+    self.reset();
+  }
+
+  fn get_hold_length(sample_rate: f32, hold_time: f32) -> u32 {
+    (hold_time * 0.001 * sample_rate - 1.0) as u32
+  }
+
+  pub fn reset(&mut self) {
+    self.current_min = self.limit;
+    self.next_min = self.limit;
+    self.next_hold_length = 0;
+    self.hold_index = self.hold_length;
+  }
+
   /// If gain_reduction is below current current_min, then replace current_min immediately. <br />
   /// Else if hold_time has passed, then reset min gain_reduction that occured during the hold_time window and reset the hold_time to when the next peak occured in the hold_time window. <br />
   /// Else, keep the current_min value untouched for hold_time.
@@ -163,4 +182,20 @@ mod tests {
 
     assert_eq!(moving_min.process(1.2), 0.5);
   }
+
+  #[test]
+  fn should_pass_unity_gain_when_there_is_no_gain_reduction() {
+    let mut moving_min = MovingMin::new(1000., 4., 2., 1.);
+
+    assert_eq!(moving_min.process(1.), 1.);
+    assert_eq!(moving_min.process(0.5), 0.5);
+    assert_eq!(moving_min.process(1.), 0.5);
+    assert_eq!(moving_min.process(1.), 0.5);
+    assert_eq!(moving_min.process(1.), 0.5);
+    assert_eq!(moving_min.process(1.), 0.5);
+    assert_eq!(moving_min.process(1.), 0.5);
+
+    assert_eq!(moving_min.process(1.), 1.);
+    assert_eq!(moving_min.process(1.), 1.);
+  }
 }
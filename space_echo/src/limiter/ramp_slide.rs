@@ -1,6 +1,9 @@
 use crate::shared::float_ext::FloatExt;
 
 pub struct RampSlide {
+  sample_rate: f32,
+  slide_up: f32,
+  ramp_down: f32,
   slide_up_factor: f32,
   z: f32,
   ramp_prev: f32,
@@ -15,6 +18,9 @@ impl RampSlide {
     let ramp_time = ramp_down.mstosamps(sample_rate);
 
     Self {
+      sample_rate,
+      slide_up,
+      ramp_down,
       z: 1.,
       slide_up_factor: slide_up.mstosamps(sample_rate).recip(),
       ramp_prev: 1.,
@@ -25,6 +31,30 @@ impl RampSlide {
     }
   }
 
+  pub fn set_sample_rate(&mut self, sample_rate: f32) {
+    self.sample_rate = sample_rate;
+    let ramp_time = self.ramp_down.mstosamps(sample_rate);
+    self.slide_up_factor = self.slide_up.mstosamps(sample_rate).recip();
+    self.ramp_time = ramp_time as usize - 1;
+    self.ramp_factor = ramp_time.recip();
+    self.reset();
+  }
+
+  /// Sets the slide up time in milliseconds without resetting the current value.
+  pub fn set_slide_up(&mut self, slide_up: f32) {
+    if slide_up != self.slide_up {
+      self.slide_up = slide_up;
+      self.slide_up_factor = slide_up.mstosamps(self.sample_rate).recip();
+    }
+  }
+
+  pub fn reset(&mut self) {
+    self.z = 1.;
+    self.ramp_prev = 1.;
+    self.ramp_index = 0;
+    self.ramp_step_size = 0.;
+  }
+
   pub fn process(&mut self, input: f32) -> f32 {
     let difference = input - self.z;
     if difference.abs() <= f32::EPSILON {
@@ -122,4 +152,16 @@ mod tests {
     assert_eq!(ramp_slide.process(0.49), 0.49);
     assert_eq!(ramp_slide.process(0.49), 0.49);
   }
+
+  #[test]
+  fn should_slide_up_by_the_new_slide_up_time() {
+    let mut ramp_slide = RampSlide::new(1000., 2., 1.);
+
+    assert_eq!(ramp_slide.process(0.), 0.);
+    ramp_slide.set_slide_up(4.);
+    assert_eq!(ramp_slide.process(1.), 0.25);
+    assert_eq!(ramp_slide.process(1.), 0.4375);
+    ramp_slide.set_slide_up(1.);
+    assert_eq!(ramp_slide.process(1.), 1.);
+  }
 }
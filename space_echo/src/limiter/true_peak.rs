@@ -0,0 +1,85 @@
+use {
+  crate::oversampling::{
+    HalfBandFilter, STAGE_1_COEFFICIENTS, STAGE_1_LENGTH, STAGE_2_COEFFICIENTS, STAGE_2_LENGTH,
+  },
+  std::simd::{f32x2, num::SimdFloat},
+};
+
+/// Estimates the peaks between the samples by upsampling the input four times with the half-band filters of the oversampler.
+pub struct TruePeak {
+  upsampler_2x: HalfBandFilter<STAGE_1_LENGTH>,
+  upsampler_4x: HalfBandFilter<STAGE_2_LENGTH>,
+}
+
+impl TruePeak {
+  /// The delay of the estimate in samples, rounded up. The 2x stage delays by STAGE_1_LENGTH - 1 samples at twice the rate
+  /// and the 4x stage by STAGE_2_LENGTH - 1 samples at four times the rate.
+  pub const LATENCY: usize = (2 * (STAGE_1_LENGTH - 1) + STAGE_2_LENGTH - 1).div_ceil(4);
+
+  pub fn new() -> Self {
+    Self {
+      upsampler_2x: HalfBandFilter::new(STAGE_1_COEFFICIENTS),
+      upsampler_4x: HalfBandFilter::new(STAGE_2_COEFFICIENTS),
+    }
+  }
+
+  pub fn reset(&mut self) {
+    self.upsampler_2x.reset();
+    self.upsampler_4x.reset();
+  }
+
+  /// Returns the highest absolute value of the four upsampled samples of each channel.
+  pub fn process(&mut self, input: (f32, f32)) -> (f32, f32) {
+    let peak = self
+      .upsampler_2x
+      .upsample(f32x2::from_array([input.0, input.1]))
+      .into_iter()
+      .flat_map(|x| self.upsampler_4x.upsample(x))
+      .fold(f32x2::splat(0.), |peak, x| peak.simd_max(x.abs()));
+    (peak[0], peak[1])
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::TruePeak;
+  use std::f32::consts::{FRAC_PI_2, FRAC_PI_4};
+
+  #[test]
+  fn should_find_the_peak_between_the_samples() {
+    let mut true_peak = TruePeak::new();
+    // a sine at a quarter of the sample rate with a phase of 45 degrees never hits its peak on a sample
+    let peaks: Vec<f32> = (0..200)
+      .map(|i| {
+        let x = (i as f32 * FRAC_PI_2 + FRAC_PI_4).sin();
+        assert!(x.abs() < 0.71);
+        true_peak.process((x, x)).0
+      })
+      .collect();
+
+    // the sine peaks every other sample, and the upsampled samples of the others are 22.5 degrees off
+    assert!(peaks[100..].iter().all(|peak| *peak > 0.92));
+    assert!((peaks[100..].iter().fold(0_f32, |max, peak| max.max(*peak)) - 1.).abs() < 0.001);
+  }
+
+  #[test]
+  fn should_delay_the_estimate_by_at_most_the_latency() {
+    let mut true_peak = TruePeak::new();
+    let impulse_response: Vec<f32> = (0..32)
+      .map(|i| {
+        true_peak
+          .process(if i == 0 { (1., 1.) } else { (0., 0.) })
+          .0
+      })
+      .collect();
+    let peak = impulse_response
+      .iter()
+      .enumerate()
+      .max_by(|a, b| a.1.total_cmp(b.1))
+      .map(|(i, _)| i)
+      .unwrap();
+
+    assert!(peak <= TruePeak::LATENCY);
+    assert!(peak + 1 >= TruePeak::LATENCY);
+  }
+}
@@ -0,0 +1,187 @@
+use {
+  crate::shared::float_ext::FloatExt,
+  std::sync::atomic::{AtomicU32, Ordering},
+};
+
+/// The lowest level the meters report in decibels.
+pub const MIN_METER_LEVEL: f32 = -100.;
+/// How fast the peak meters fall in decibels per second.
+const PEAK_RELEASE: f32 = 24.;
+
+/// An f32 that can be shared between threads without locking, by storing its bits in an `AtomicU32`.
+struct AtomicF32(AtomicU32);
+
+impl AtomicF32 {
+  fn new(value: f32) -> Self {
+    Self(AtomicU32::new(value.to_bits()))
+  }
+
+  fn load(&self) -> f32 {
+    f32::from_bits(self.0.load(Ordering::Relaxed))
+  }
+
+  fn store(&self, value: f32) {
+    self.0.store(value.to_bits(), Ordering::Relaxed)
+  }
+}
+
+/// The highest and lowest values that are measured during a block, as linear amplitude values.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BlockLevels {
+  pub input: (f32, f32),
+  pub output: (f32, f32),
+  pub feedback: f32,
+  pub gain_compensation: f32,
+  pub duck_gain: f32,
+}
+
+impl Default for BlockLevels {
+  fn default() -> Self {
+    Self {
+      input: (0., 0.),
+      output: (0., 0.),
+      feedback: 0.,
+      gain_compensation: 1.,
+      duck_gain: 1.,
+    }
+  }
+}
+
+/// The levels of the processor in decibels. The audio thread writes them once per block, any other thread can read them without locking.
+pub struct Meters {
+  input: [AtomicF32; 2],
+  output: [AtomicF32; 2],
+  feedback: AtomicF32,
+  gain_compensation: AtomicF32,
+  duck_gain_reduction: AtomicF32,
+  limiter_gain_reduction: AtomicF32,
+}
+
+impl Meters {
+  pub fn new() -> Self {
+    Self {
+      input: [
+        AtomicF32::new(MIN_METER_LEVEL),
+        AtomicF32::new(MIN_METER_LEVEL),
+      ],
+      output: [
+        AtomicF32::new(MIN_METER_LEVEL),
+        AtomicF32::new(MIN_METER_LEVEL),
+      ],
+      feedback: AtomicF32::new(MIN_METER_LEVEL),
+      gain_compensation: AtomicF32::new(0.),
+      duck_gain_reduction: AtomicF32::new(0.),
+      limiter_gain_reduction: AtomicF32::new(0.),
+    }
+  }
+
+  /// The peak level of the left and right input.
+  pub fn input(&self) -> (f32, f32) {
+    (self.input[0].load(), self.input[1].load())
+  }
+
+  /// The peak level of the left and right output.
+  pub fn output(&self) -> (f32, f32) {
+    (self.output[0].load(), self.output[1].load())
+  }
+
+  /// The peak level of the repeats that are fed back onto the tape.
+  pub fn feedback(&self) -> f32 {
+    self.feedback.load()
+  }
+
+  /// The gain that keeps the echo from getting louder at high feedback. It's 0 or lower.
+  pub fn gain_compensation(&self) -> f32 {
+    self.gain_compensation.load()
+  }
+
+  /// The attenuation of the duck as a positive number.
+  pub fn duck_gain_reduction(&self) -> f32 {
+    self.duck_gain_reduction.load()
+  }
+
+  /// The attenuation of the limiter as a positive number.
+  pub fn limiter_gain_reduction(&self) -> f32 {
+    self.limiter_gain_reduction.load()
+  }
+
+  /// Publishes the levels of a block. The peak meters hold the highest level and fall by the peak release.
+  pub fn update(&self, levels: &BlockLevels, limiter_gain_reduction: f32, block_duration: f32) {
+    let release = PEAK_RELEASE * block_duration;
+    Self::update_peak(&self.input[0], levels.input.0, release);
+    Self::update_peak(&self.input[1], levels.input.1, release);
+    Self::update_peak(&self.output[0], levels.output.0, release);
+    Self::update_peak(&self.output[1], levels.output.1, release);
+    Self::update_peak(&self.feedback, levels.feedback, release);
+    self
+      .gain_compensation
+      .store(Self::to_db(levels.gain_compensation));
+    self
+      .duck_gain_reduction
+      .store(-Self::to_db(levels.duck_gain));
+    self.limiter_gain_reduction.store(limiter_gain_reduction);
+  }
+
+  pub fn reset(&self) {
+    self.update(&BlockLevels::default(), 0., f32::INFINITY);
+  }
+
+  fn update_peak(meter: &AtomicF32, peak: f32, release: f32) {
+    meter.store(Self::to_db(peak).max(meter.load() - release));
+  }
+
+  fn to_db(level: f32) -> f32 {
+    level.atodb().max(MIN_METER_LEVEL)
+  }
+}
+
+impl Default for Meters {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{BlockLevels, Meters, MIN_METER_LEVEL};
+
+  #[test]
+  fn should_hold_the_peak_and_fall_by_the_release() {
+    let meters = Meters::new();
+    meters.update(
+      &BlockLevels {
+        input: (1., 0.5),
+        ..Default::default()
+      },
+      0.,
+      0.01,
+    );
+    assert_eq!(meters.input().0, 0.);
+    assert!((meters.input().1 + 6.0206).abs() < 0.001);
+
+    meters.update(&BlockLevels::default(), 0., 0.5);
+    assert_eq!(meters.input().0, -12.);
+    assert!((meters.input().1 + 18.0206).abs() < 0.001);
+
+    meters.reset();
+    assert_eq!(meters.input(), (MIN_METER_LEVEL, MIN_METER_LEVEL));
+  }
+
+  #[test]
+  fn should_report_the_gain_reduction_as_a_positive_number() {
+    let meters = Meters::new();
+    meters.update(
+      &BlockLevels {
+        gain_compensation: 0.5,
+        duck_gain: 0.25,
+        ..Default::default()
+      },
+      3.,
+      0.01,
+    );
+
+    assert!((meters.gain_compensation() + 6.0206).abs() < 0.001);
+    assert!((meters.duck_gain_reduction() - 12.0412).abs() < 0.001);
+    assert_eq!(meters.limiter_gain_reduction(), 3.);
+  }
+}
@@ -0,0 +1,174 @@
+mod half_band_filter;
+pub use half_band_filter::HalfBandFilter;
+use {crate::params::Oversampling, std::simd::f32x2};
+
+pub const MAX_OVERSAMPLING_FACTOR: usize = 4;
+/// The latency in samples at the base sample rate when oversampling 4 times.
+pub const MAX_OVERSAMPLING_LATENCY: usize = STAGE_1_LENGTH - 1 + STAGE_2_LENGTH / 2;
+
+pub const STAGE_1_LENGTH: usize = 20;
+pub const STAGE_2_LENGTH: usize = 8;
+// 39 and 15 tap Kaiser windowed half-band filters with a beta of 7
+pub const STAGE_1_COEFFICIENTS: [f32; STAGE_1_LENGTH] = [
+  -0.000099366,
+  0.000600699,
+  -0.001840501,
+  0.004345518,
+  -0.008843229,
+  0.01639655,
+  -0.02886926,
+  0.050680205,
+  -0.0978214,
+  0.3154508,
+  0.3154508,
+  -0.0978214,
+  0.050680205,
+  -0.02886926,
+  0.01639655,
+  -0.008843229,
+  0.004345518,
+  -0.001840501,
+  0.000600699,
+  -0.000099366,
+];
+pub const STAGE_2_COEFFICIENTS: [f32; STAGE_2_LENGTH] = [
+  -0.000269671,
+  0.009397768,
+  -0.056930438,
+  0.29780233,
+  0.29780233,
+  -0.056930438,
+  0.009397768,
+  -0.000269671,
+];
+
+/// Runs a nonlinear process at 2 or 4 times the sample rate, to keep its harmonics from aliasing.
+pub struct Oversampler {
+  oversampling: Oversampling,
+  upsampler_2x: HalfBandFilter<STAGE_1_LENGTH>,
+  downsampler_2x: HalfBandFilter<STAGE_1_LENGTH>,
+  upsampler_4x: HalfBandFilter<STAGE_2_LENGTH>,
+  downsampler_4x: HalfBandFilter<STAGE_2_LENGTH>,
+  // delays the 4x path by half a sample, so the latency adds up to whole samples
+  alignment: f32x2,
+}
+
+impl Oversampler {
+  pub fn new() -> Self {
+    Self {
+      oversampling: Oversampling::default(),
+      upsampler_2x: HalfBandFilter::new(STAGE_1_COEFFICIENTS),
+      downsampler_2x: HalfBandFilter::new(STAGE_1_COEFFICIENTS),
+      upsampler_4x: HalfBandFilter::new(STAGE_2_COEFFICIENTS),
+      downsampler_4x: HalfBandFilter::new(STAGE_2_COEFFICIENTS),
+      alignment: f32x2::splat(0.),
+    }
+  }
+
+  pub fn set_oversampling(&mut self, oversampling: Oversampling) {
+    self.oversampling = oversampling;
+    self.reset();
+  }
+
+  pub fn reset(&mut self) {
+    self.upsampler_2x.reset();
+    self.downsampler_2x.reset();
+    self.upsampler_4x.reset();
+    self.downsampler_4x.reset();
+    self.alignment = f32x2::splat(0.);
+  }
+
+  /// The latency in samples at the base sample rate.
+  pub fn latency(&self) -> usize {
+    match self.oversampling {
+      Oversampling::X1 => 0,
+      Oversampling::X2 => STAGE_1_LENGTH - 1,
+      Oversampling::X4 => MAX_OVERSAMPLING_LATENCY,
+    }
+  }
+
+  /// Calls the process for every sample at the oversampled rate.
+  pub fn process(&mut self, input: f32x2, mut process: impl FnMut(f32x2) -> f32x2) -> f32x2 {
+    match self.oversampling {
+      Oversampling::X1 => process(input),
+      Oversampling::X2 => {
+        let upsampled = self.upsampler_2x.upsample(input);
+        self.downsampler_2x.downsample(upsampled.map(process))
+      }
+      Oversampling::X4 => {
+        let upsampled = self.upsampler_2x.upsample(input).map(|x| {
+          let upsampled = self.upsampler_4x.upsample(x).map(&mut process);
+          self.downsampler_4x.downsample(upsampled)
+        });
+        let aligned = [self.alignment, upsampled[0]];
+        self.alignment = upsampled[1];
+        self.downsampler_2x.downsample(aligned)
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::Oversampler;
+  use crate::params::Oversampling;
+  use std::{
+    f32::consts::TAU,
+    simd::{f32x2, num::SimdFloat},
+  };
+
+  #[test]
+  fn should_delay_by_the_reported_latency() {
+    [Oversampling::X1, Oversampling::X2, Oversampling::X4]
+      .into_iter()
+      .for_each(|oversampling| {
+        let mut oversampler = Oversampler::new();
+        oversampler.set_oversampling(oversampling);
+        let impulse_response: Vec<f32> = (0..64)
+          .map(|i| {
+            let input = f32x2::splat(if i == 0 { 1. } else { 0. });
+            oversampler.process(input, |x| x)[0]
+          })
+          .collect();
+        let peak = impulse_response
+          .iter()
+          .enumerate()
+          .max_by(|a, b| a.1.abs().total_cmp(&b.1.abs()))
+          .map(|(i, _)| i);
+        assert_eq!(peak, Some(oversampler.latency()));
+        assert!((impulse_response.iter().sum::<f32>() - 1.).abs() < 1e-4);
+      });
+  }
+
+  #[test]
+  fn should_remove_harmonics_above_nyquist() {
+    let sample_rate = 48000.;
+    let freq = 5000.;
+    let saturate = |x: f32x2| x.simd_clamp(f32x2::splat(-0.2), f32x2::splat(0.2));
+
+    // the 5th harmonic of a clipped 5 kHz sine folds back to 23 kHz, the 7th to 13 kHz
+    let get_alias_level = |oversampling: Oversampling| {
+      let mut oversampler = Oversampler::new();
+      oversampler.set_oversampling(oversampling);
+      let output: Vec<f32> = (0..4800)
+        .map(|i| {
+          let input = f32x2::splat((i as f32 / sample_rate * freq * TAU).sin());
+          oversampler.process(input, saturate)[0]
+        })
+        .skip(800)
+        .collect();
+      let (re, im) = output
+        .iter()
+        .enumerate()
+        .fold((0., 0.), |(re, im), (i, x)| {
+          let phase = i as f32 / sample_rate * 13000. * TAU;
+          (re + x * phase.cos(), im + x * phase.sin())
+        });
+      (re * re + im * im).sqrt() / output.len() as f32
+    };
+
+    let alias_level = get_alias_level(Oversampling::X1);
+    assert!(get_alias_level(Oversampling::X2) < alias_level * 0.5);
+    assert!(get_alias_level(Oversampling::X4) < alias_level * 0.1);
+  }
+}
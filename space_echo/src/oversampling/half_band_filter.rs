@@ -0,0 +1,59 @@
+/* Polyphase half-band FIR filter. Every other tap of a half-band filter is zero, so only the even taps are stored. */
+use std::simd::f32x2;
+
+pub struct HalfBandFilter<const N: usize> {
+  coefficients: [f32; N],
+  even: [f32x2; N],
+  odd: [f32x2; N],
+}
+
+impl<const N: usize> HalfBandFilter<N> {
+  /// The delay of the center tap in input samples.
+  const CENTER_DELAY: usize = N / 2 - 1;
+
+  /// Takes the even taps of a filter of length 2 * N - 1, which should add up to 0.5.
+  pub fn new(coefficients: [f32; N]) -> Self {
+    Self {
+      coefficients,
+      even: [f32x2::splat(0.); N],
+      odd: [f32x2::splat(0.); N],
+    }
+  }
+
+  pub fn reset(&mut self) {
+    self.even.fill(f32x2::splat(0.));
+    self.odd.fill(f32x2::splat(0.));
+  }
+
+  /// Returns two samples at the doubled sample rate for every input sample.
+  pub fn upsample(&mut self, input: f32x2) -> [f32x2; 2] {
+    Self::push(&mut self.even, input);
+
+    [
+      self.convolve() * f32x2::splat(2.),
+      self.even[Self::CENTER_DELAY],
+    ]
+  }
+
+  /// Returns one sample at the halved sample rate for every two input samples.
+  pub fn downsample(&mut self, input: [f32x2; 2]) -> f32x2 {
+    Self::push(&mut self.even, input[0]);
+    Self::push(&mut self.odd, input[1]);
+
+    self.convolve() + self.odd[Self::CENTER_DELAY + 1] * f32x2::splat(0.5)
+  }
+
+  fn push(history: &mut [f32x2; N], input: f32x2) {
+    history.copy_within(0..N - 1, 1);
+    history[0] = input;
+  }
+
+  fn convolve(&self) -> f32x2 {
+    self
+      .coefficients
+      .iter()
+      .zip(self.even)
+      .map(|(coefficient, x)| x * f32x2::splat(*coefficient))
+      .sum()
+  }
+}
@@ -1,30 +1,418 @@
 mod smooth;
-use crate::{duck::MIN_DUCK_THRESHOLD, FloatExt};
+use crate::{
+  duck::MIN_DUCK_THRESHOLD, shared::delay_line::Interpolation, tape_heads::get_head_mode, FloatExt,
+};
 pub use smooth::Smoother;
-use smooth::{ExponentialSmooth, LogarithmicSmooth};
+use {
+  smooth::{ExponentialSmooth, LogarithmicSmooth},
+  std::array,
+};
 
 const TIME_SMOOTHING_FACTOR: f32 = 0.25;
+pub const MAX_BLOCK_SIZE: usize = 32;
+
+/// The smoothed parameter values for each sample of a block. <br />
+/// Apart from the size and the predelay, the parameters of the reverb, the saturation and the pitch shifter are only updated once per block.
+#[derive(Default)]
+pub struct SmoothedParams {
+  pub input_level: [f32; MAX_BLOCK_SIZE],
+  pub time_left: [f32; MAX_BLOCK_SIZE],
+  pub time_right: [f32; MAX_BLOCK_SIZE],
+  pub feedback: [f32; MAX_BLOCK_SIZE],
+  pub head_gains: [[f32; 3]; MAX_BLOCK_SIZE],
+  pub head_feedback: [[f32; 3]; MAX_BLOCK_SIZE],
+  pub flutter_gain: [f32; MAX_BLOCK_SIZE],
+  pub highpass_freq: [f32; MAX_BLOCK_SIZE],
+  pub lowpass_freq: [f32; MAX_BLOCK_SIZE],
+  pub reverb: [f32; MAX_BLOCK_SIZE],
+  pub freeze: [f32; MAX_BLOCK_SIZE],
+  pub dry_reverb_send: [f32; MAX_BLOCK_SIZE],
+  pub stereo: [f32; MAX_BLOCK_SIZE],
+  pub output_level: [f32; MAX_BLOCK_SIZE],
+  pub mix: [f32; MAX_BLOCK_SIZE],
+  pub duck_threshold: [f32; MAX_BLOCK_SIZE],
+  pub filter_fader: [f32; MAX_BLOCK_SIZE],
+  pub size: [f32; MAX_BLOCK_SIZE],
+  pub predelay: [f32; MAX_BLOCK_SIZE],
+  pub drive: f32,
+  pub width: f32,
+  pub bias: f32,
+  pub shimmer_interval: f32,
+  pub shimmer: f32,
+  pub decay: f32,
+  pub decay_low: f32,
+  pub decay_high: f32,
+  pub damping: f32,
+  pub modulation_rate: f32,
+  pub modulation_depth: f32,
+  pub duck_highpass_freq: f32,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum ChannelMode {
+  #[default]
+  Stereo,
+  PingPong,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum TimeMode {
+  /// Smooths the delay time, which changes the pitch like a tape machine.
+  #[default]
+  Repitch,
+  /// Crossfades to the new delay time.
+  Fade,
+  /// Plays the tape backwards in crossfaded segments that follow the delay time.
+  Reverse,
+}
+
+/// The position of the head mode selector. <br />
+/// Mode 1 to 12 follow the mode selector of the RE-201: 1 to 4 are echo only, 5 to 11 are echo with reverb and 12 is reverb only.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum HeadMode {
+  /// Plays back the third head with reverb, like a single head delay, and feeds back the filtered echo.
+  #[default]
+  SingleHead,
+  /// Head 1.
+  Mode1,
+  /// Head 2.
+  Mode2,
+  /// Head 3.
+  Mode3,
+  /// Head 2 and 3.
+  Mode4,
+  /// Head 1 with reverb.
+  Mode5,
+  /// Head 2 with reverb.
+  Mode6,
+  /// Head 3 with reverb.
+  Mode7,
+  /// Head 1 and 2 with reverb.
+  Mode8,
+  /// Head 2 and 3 with reverb.
+  Mode9,
+  /// Head 1 and 3 with reverb.
+  Mode10,
+  /// All heads with reverb.
+  Mode11,
+  /// Reverb only.
+  Mode12,
+}
+
+impl From<i32> for HeadMode {
+  /// Maps the position of the selector, from 0 for the single head to 12. Positions outside the selector are clamped to it.
+  fn from(position: i32) -> Self {
+    match position.clamp(0, 12) {
+      1 => HeadMode::Mode1,
+      2 => HeadMode::Mode2,
+      3 => HeadMode::Mode3,
+      4 => HeadMode::Mode4,
+      5 => HeadMode::Mode5,
+      6 => HeadMode::Mode6,
+      7 => HeadMode::Mode7,
+      8 => HeadMode::Mode8,
+      9 => HeadMode::Mode9,
+      10 => HeadMode::Mode10,
+      11 => HeadMode::Mode11,
+      12 => HeadMode::Mode12,
+      _ => HeadMode::SingleHead,
+    }
+  }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum ReverbType {
+  /// A feedback delay network with early reflections.
+  #[default]
+  Hall,
+  /// A spring tank with chirping repeats and a boing on transients.
+  Spring,
+}
+
+/// The order in which the echo and the reverb process the signal.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Routing {
+  /// The echo feeds the reverb.
+  #[default]
+  EchoIntoReverb,
+  /// The reverb feeds the echo, so every repeat is reverberated.
+  ReverbIntoEcho,
+  /// The echo and the reverb both process the input and are mixed by the reverb amount.
+  Parallel,
+  /// The reverb processes the dry signal only and the echo stays dry.
+  ReverbOnDryOnly,
+}
+
+/// The signal the duck listens to.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum DuckSource {
+  /// The input of the effect.
+  #[default]
+  Input,
+  /// The sidechain input. Falls back to the input when no sidechain is connected.
+  Sidechain,
+}
+
+/// The signal the duck attenuates.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum DuckTarget {
+  /// The output of the echo and the reverb.
+  #[default]
+  Wet,
+  /// The repeats, before they reach the reverb.
+  Echo,
+  /// The reverb return only.
+  Reverb,
+  /// The feedback amount, so the repeats build up in the gaps of the input.
+  Feedback,
+}
+
+/// How the duck measures the level of its input.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum DuckDetector {
+  /// Follows the peaks of the input.
+  #[default]
+  Peak,
+  /// Follows the average loudness of the input, so short transients duck less.
+  Rms,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum SaturationMode {
+  /// A static saturation curve that's blended in by the loudness of the feedback signal.
+  #[default]
+  Cheap,
+  /// A tape hysteresis model that's set by the drive, width and bias.
+  Hysteresis,
+}
+
+/// The oversampling factor of the saturation, the echo filters and the limiter.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Oversampling {
+  #[default]
+  X1,
+  X2,
+  X4,
+}
+
+impl Oversampling {
+  pub fn factor(&self) -> usize {
+    match self {
+      Oversampling::X1 => 1,
+      Oversampling::X2 => 2,
+      Oversampling::X4 => 4,
+    }
+  }
+}
+
+/// The unsmoothed parameter values as they're exposed to the user.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ParamValues {
+  /// Input level in decibels.
+  pub input_level: f32,
+  pub channel_mode: ChannelMode,
+  pub time_mode: TimeMode,
+  pub head_mode: HeadMode,
+  /// Makes the right delay time follow the left delay time.
+  pub time_link: bool,
+  /// Left delay time in milliseconds. Times below [`crate::SpaceEcho::min_time`] are clamped for each head.
+  pub time_left: f32,
+  /// Right delay time in milliseconds. Times below [`crate::SpaceEcho::min_time`] are clamped for each head.
+  pub time_right: f32,
+  pub feedback: f32,
+  pub head_feedback: [f32; 3],
+  pub wow_and_flutter: f32,
+  /// Highpass frequency in hertz.
+  pub highpass_freq: f32,
+  pub highpass_res: f32,
+  /// Lowpass frequency in hertz.
+  pub lowpass_freq: f32,
+  pub lowpass_res: f32,
+  pub reverb: f32,
+  /// The reverb time in seconds, the time it takes the tail to fall by 60 dB.
+  pub decay: f32,
+  /// Multiplies the reverb time of the low frequencies in the hall.
+  pub decay_low: f32,
+  /// Multiplies the reverb time of the high frequencies in the hall.
+  pub decay_high: f32,
+  pub reverb_type: ReverbType,
+  pub routing: Routing,
+  /// The room size of the hall relative to its default size, up to the max size of the [`crate::SpaceEchoConfig`].
+  pub size: f32,
+  /// The pre-delay of the reverb in milliseconds.
+  pub predelay: f32,
+  /// The cutoff frequency of the damping in the hall in hertz.
+  pub damping: f32,
+  /// The rate of the delay time modulation in the hall in hertz.
+  pub modulation_rate: f32,
+  /// The depth of the delay time modulation in the hall in milliseconds.
+  pub modulation_depth: f32,
+  pub stereo: f32,
+  pub duck: f32,
+  pub duck_source: DuckSource,
+  pub duck_target: DuckTarget,
+  /// The time in milliseconds it takes the duck to attenuate.
+  pub duck_attack: f32,
+  /// The time in milliseconds it takes the duck to recover.
+  pub duck_release: f32,
+  /// The time in milliseconds the duck keeps attenuating after the input has dropped below the threshold.
+  pub duck_hold: f32,
+  /// The maximum attenuation of the duck in decibels.
+  pub duck_depth: f32,
+  pub duck_detector: DuckDetector,
+  /// The cutoff frequency of the highpass filter on the input of the duck detector in hertz.
+  pub duck_highpass_freq: f32,
+  /// Output level in decibels.
+  pub output_level: f32,
+  pub mix: f32,
+  pub limiter: bool,
+  /// The maximum output level of the limiter in dBFS.
+  pub limiter_ceiling: f32,
+  /// The time in milliseconds it takes the limiter to recover.
+  pub limiter_release: f32,
+  /// How much the channels share their gain reduction, from 0 for independent channels to 1 for fully linked.
+  pub limiter_link: f32,
+  /// Detects the peaks between the samples, so the output doesn't clip after conversion to analog.
+  pub limiter_true_peak: bool,
+  /// Delays the output by the latency of the limiter while it's off, so the latency doesn't change when the limiter is toggled.
+  pub constant_latency: bool,
+  pub hold: bool,
+  /// Sustains the reverb tail and stops new input from reaching the reverb.
+  pub freeze: bool,
+  pub saturation_mode: SaturationMode,
+  /// How hard the tape is magnetised in hysteresis mode, from 0 to 1.
+  pub drive: f32,
+  /// The width of the hysteresis loop, from 0 to 1.
+  pub width: f32,
+  /// The offset of the tape bias from its ideal setting, from 0 to 1. Higher values add even harmonics.
+  pub bias: f32,
+  /// The pitch shift of each repeat in semitones, from -12 to 12.
+  pub shimmer_interval: f32,
+  /// How much of the pitch shifted signal is fed back, from 0 to 1.
+  pub shimmer: f32,
+  pub oversampling: Oversampling,
+  /// The interpolation of the tape heads and the reverb taps.
+  pub interpolation: Interpolation,
+}
+
+impl Default for ParamValues {
+  fn default() -> Self {
+    Self {
+      input_level: 0.,
+      channel_mode: ChannelMode::Stereo,
+      time_mode: TimeMode::Repitch,
+      head_mode: HeadMode::SingleHead,
+      time_link: true,
+      time_left: 250.,
+      time_right: 250.,
+      feedback: 0.5,
+      head_feedback: [1.; 3],
+      wow_and_flutter: 0.,
+      highpass_freq: 20.,
+      highpass_res: 0.,
+      lowpass_freq: 6000.,
+      lowpass_res: 0.,
+      reverb: 0.,
+      decay: 1.5,
+      decay_low: 1.,
+      decay_high: 1.,
+      reverb_type: ReverbType::Hall,
+      routing: Routing::EchoIntoReverb,
+      size: 1.,
+      predelay: 0.,
+      damping: 6000.,
+      modulation_rate: 3.7,
+      modulation_depth: 1.,
+      stereo: 1.,
+      duck: 0.,
+      duck_source: DuckSource::Input,
+      duck_target: DuckTarget::Wet,
+      duck_attack: 1.5,
+      duck_release: 120.,
+      duck_hold: 0.,
+      duck_depth: 60.,
+      duck_detector: DuckDetector::Peak,
+      duck_highpass_freq: 20.,
+      output_level: 0.,
+      mix: 0.5,
+      limiter: false,
+      limiter_ceiling: -0.3,
+      limiter_release: 40.,
+      limiter_link: 1.,
+      limiter_true_peak: false,
+      constant_latency: false,
+      hold: false,
+      freeze: false,
+      saturation_mode: SaturationMode::Cheap,
+      drive: 0.5,
+      width: 0.5,
+      bias: 0.,
+      shimmer_interval: 12.,
+      shimmer: 0.,
+      oversampling: Oversampling::X1,
+      interpolation: Interpolation::Linear,
+    }
+  }
+}
 
 pub struct Params {
   pub input_level: ExponentialSmooth,
-  pub channel_mode: i32,
-  pub time_mode: i32,
+  pub channel_mode: ChannelMode,
+  pub time_mode: TimeMode,
+  pub head_mode: HeadMode,
   time_left: LogarithmicSmooth,
   time_right: LogarithmicSmooth,
   pub feedback: ExponentialSmooth,
+  pub head_gains: [ExponentialSmooth; 3],
+  pub head_feedback: [ExponentialSmooth; 3],
   pub flutter_gain: ExponentialSmooth,
   pub highpass_freq: ExponentialSmooth,
   pub highpass_res: f32,
   pub lowpass_freq: ExponentialSmooth,
   pub lowpass_res: f32,
   pub reverb: ExponentialSmooth,
+  pub freeze: ExponentialSmooth,
   pub decay: ExponentialSmooth,
+  pub decay_low: ExponentialSmooth,
+  pub decay_high: ExponentialSmooth,
+  pub reverb_type: ReverbType,
+  pub routing: Routing,
+  pub size: ExponentialSmooth,
+  pub predelay: ExponentialSmooth,
+  pub damping: ExponentialSmooth,
+  pub modulation_rate: ExponentialSmooth,
+  pub modulation_depth: ExponentialSmooth,
+  pub dry_reverb_send: ExponentialSmooth,
   pub stereo: ExponentialSmooth,
-  pub duck_threshold: f32,
+  pub duck_threshold: ExponentialSmooth,
+  pub duck_source: DuckSource,
+  pub duck_target: DuckTarget,
+  pub duck_attack: f32,
+  pub duck_release: f32,
+  pub duck_hold: f32,
+  pub duck_depth: f32,
+  pub duck_detector: DuckDetector,
+  pub duck_highpass_freq: ExponentialSmooth,
   pub output_level: ExponentialSmooth,
   pub mix: ExponentialSmooth,
   pub limiter: bool,
+  pub limiter_ceiling: f32,
+  pub limiter_release: f32,
+  pub limiter_link: f32,
+  pub limiter_true_peak: bool,
+  pub constant_latency: bool,
   pub filter_fader: ExponentialSmooth,
+  pub saturation_mode: SaturationMode,
+  pub drive: ExponentialSmooth,
+  pub width: ExponentialSmooth,
+  pub bias: ExponentialSmooth,
+  pub shimmer_interval: ExponentialSmooth,
+  pub shimmer: ExponentialSmooth,
+  pub oversampling: Oversampling,
+  pub interpolation: Interpolation,
+  values: ParamValues,
+  needs_update: bool,
+  smoothed_params: SmoothedParams,
   is_initialized: bool,
 }
 
@@ -32,107 +420,859 @@ impl Params {
   pub fn new(sample_rate: f32) -> Self {
     Self {
       input_level: ExponentialSmooth::new(sample_rate, 7.),
-      channel_mode: 0,
-      time_mode: 0,
+      channel_mode: ChannelMode::Stereo,
+      time_mode: TimeMode::Repitch,
+      head_mode: HeadMode::SingleHead,
       time_left: LogarithmicSmooth::new(sample_rate, TIME_SMOOTHING_FACTOR),
       time_right: LogarithmicSmooth::new(sample_rate, TIME_SMOOTHING_FACTOR),
       feedback: ExponentialSmooth::new(sample_rate, 7.),
+      head_gains: array::from_fn(|_| ExponentialSmooth::new(sample_rate, 7.)),
+      head_feedback: array::from_fn(|_| ExponentialSmooth::new(sample_rate, 7.)),
       flutter_gain: ExponentialSmooth::new(sample_rate, 7.),
       highpass_freq: ExponentialSmooth::new(sample_rate, 7.),
       highpass_res: 0.,
       lowpass_freq: ExponentialSmooth::new(sample_rate, 7.),
       lowpass_res: 0.,
       reverb: ExponentialSmooth::new(sample_rate, 7.),
+      freeze: ExponentialSmooth::new(sample_rate, 7.),
       decay: ExponentialSmooth::new(sample_rate, 7.),
+      decay_low: ExponentialSmooth::new(sample_rate, 7.),
+      decay_high: ExponentialSmooth::new(sample_rate, 7.),
+      reverb_type: ReverbType::Hall,
+      routing: Routing::EchoIntoReverb,
+      size: ExponentialSmooth::new(sample_rate, 7.),
+      predelay: ExponentialSmooth::new(sample_rate, 7.),
+      damping: ExponentialSmooth::new(sample_rate, 7.),
+      modulation_rate: ExponentialSmooth::new(sample_rate, 7.),
+      modulation_depth: ExponentialSmooth::new(sample_rate, 7.),
+      dry_reverb_send: ExponentialSmooth::new(sample_rate, 7.),
       stereo: ExponentialSmooth::new(sample_rate, 7.),
-      duck_threshold: 0.,
+      duck_threshold: ExponentialSmooth::new(sample_rate, 7.),
+      duck_source: DuckSource::Input,
+      duck_target: DuckTarget::Wet,
+      duck_attack: 1.5,
+      duck_release: 120.,
+      duck_hold: 0.,
+      duck_depth: 60.,
+      duck_detector: DuckDetector::Peak,
+      duck_highpass_freq: ExponentialSmooth::new(sample_rate, 7.),
       output_level: ExponentialSmooth::new(sample_rate, 7.),
       mix: ExponentialSmooth::new(sample_rate, 7.),
       limiter: false,
+      limiter_ceiling: -0.3,
+      limiter_release: 40.,
+      limiter_link: 1.,
+      limiter_true_peak: false,
+      constant_latency: false,
       filter_fader: ExponentialSmooth::new(sample_rate, 3.5),
+      saturation_mode: SaturationMode::Cheap,
+      drive: ExponentialSmooth::new(sample_rate, 7.),
+      width: ExponentialSmooth::new(sample_rate, 7.),
+      bias: ExponentialSmooth::new(sample_rate, 7.),
+      shimmer_interval: ExponentialSmooth::new(sample_rate, 7.),
+      shimmer: ExponentialSmooth::new(sample_rate, 7.),
+      oversampling: Oversampling::X1,
+      interpolation: Interpolation::Linear,
+      values: ParamValues::default(),
+      needs_update: true,
+      smoothed_params: SmoothedParams::default(),
       is_initialized: false,
     }
   }
 
-  pub fn set(
-    &mut self,
-    input_level: f32,
-    channel_mode: i32,
-    time_mode: i32,
-    time_link: bool,
-    time_left: f32,
-    time_right: f32,
-    feedback: f32,
-    wow_and_flutter: f32,
-    highpass_freq: f32,
-    highpass_res: f32,
-    lowpass_freq: f32,
-    lowpass_res: f32,
-    reverb: f32,
-    decay: f32,
-    stereo: f32,
-    duck: f32,
-    output_level: f32,
-    mix: f32,
-    limiter: bool,
-    hold: bool,
-  ) {
+  pub fn set_sample_rate(&mut self, sample_rate: f32) {
+    self.input_level.set_sample_rate(sample_rate);
+    self.time_left.set_sample_rate(sample_rate);
+    self.time_right.set_sample_rate(sample_rate);
+    self.feedback.set_sample_rate(sample_rate);
+    self
+      .head_gains
+      .iter_mut()
+      .chain(self.head_feedback.iter_mut())
+      .for_each(|smooth| smooth.set_sample_rate(sample_rate));
+    self.flutter_gain.set_sample_rate(sample_rate);
+    self.highpass_freq.set_sample_rate(sample_rate);
+    self.lowpass_freq.set_sample_rate(sample_rate);
+    self.reverb.set_sample_rate(sample_rate);
+    self.freeze.set_sample_rate(sample_rate);
+    self.decay.set_sample_rate(sample_rate);
+    self.decay_low.set_sample_rate(sample_rate);
+    self.decay_high.set_sample_rate(sample_rate);
+    self.size.set_sample_rate(sample_rate);
+    self.predelay.set_sample_rate(sample_rate);
+    self.damping.set_sample_rate(sample_rate);
+    self.modulation_rate.set_sample_rate(sample_rate);
+    self.modulation_depth.set_sample_rate(sample_rate);
+    self.dry_reverb_send.set_sample_rate(sample_rate);
+    self.stereo.set_sample_rate(sample_rate);
+    self.duck_threshold.set_sample_rate(sample_rate);
+    self.duck_highpass_freq.set_sample_rate(sample_rate);
+    self.output_level.set_sample_rate(sample_rate);
+    self.mix.set_sample_rate(sample_rate);
+    self.filter_fader.set_sample_rate(sample_rate);
+    self.drive.set_sample_rate(sample_rate);
+    self.width.set_sample_rate(sample_rate);
+    self.bias.set_sample_rate(sample_rate);
+    self.shimmer_interval.set_sample_rate(sample_rate);
+    self.shimmer.set_sample_rate(sample_rate);
+  }
+
+  /// Makes the smoothers jump to the current values on the next block, instead of gliding from the old values.
+  pub fn reset(&mut self) {
+    self.is_initialized = false;
+    self.needs_update = true;
+  }
+
+  pub fn values(&self) -> &ParamValues {
+    &self.values
+  }
+
+  /// Sets all parameter values at once. The new values are applied at the start of the next block.
+  pub fn set(&mut self, values: ParamValues) {
+    if values != self.values {
+      self.values = values;
+      self.needs_update = true;
+    }
+  }
+
+  pub fn set_input_level(&mut self, input_level: f32) {
+    self.set(ParamValues {
+      input_level,
+      ..self.values
+    });
+  }
+
+  pub fn set_channel_mode(&mut self, channel_mode: ChannelMode) {
+    self.set(ParamValues {
+      channel_mode,
+      ..self.values
+    });
+  }
+
+  pub fn set_time_mode(&mut self, time_mode: TimeMode) {
+    self.set(ParamValues {
+      time_mode,
+      ..self.values
+    });
+  }
+
+  pub fn set_head_mode(&mut self, head_mode: HeadMode) {
+    self.set(ParamValues {
+      head_mode,
+      ..self.values
+    });
+  }
+
+  pub fn set_time_link(&mut self, time_link: bool) {
+    self.set(ParamValues {
+      time_link,
+      ..self.values
+    });
+  }
+
+  pub fn set_time_left(&mut self, time_left: f32) {
+    self.set(ParamValues {
+      time_left,
+      ..self.values
+    });
+  }
+
+  pub fn set_time_right(&mut self, time_right: f32) {
+    self.set(ParamValues {
+      time_right,
+      ..self.values
+    });
+  }
+
+  pub fn set_feedback(&mut self, feedback: f32) {
+    self.set(ParamValues {
+      feedback,
+      ..self.values
+    });
+  }
+
+  pub fn set_head_feedback(&mut self, head_feedback: [f32; 3]) {
+    self.set(ParamValues {
+      head_feedback,
+      ..self.values
+    });
+  }
+
+  pub fn set_wow_and_flutter(&mut self, wow_and_flutter: f32) {
+    self.set(ParamValues {
+      wow_and_flutter,
+      ..self.values
+    });
+  }
+
+  pub fn set_highpass_freq(&mut self, highpass_freq: f32) {
+    self.set(ParamValues {
+      highpass_freq,
+      ..self.values
+    });
+  }
+
+  pub fn set_highpass_res(&mut self, highpass_res: f32) {
+    self.set(ParamValues {
+      highpass_res,
+      ..self.values
+    });
+  }
+
+  pub fn set_lowpass_freq(&mut self, lowpass_freq: f32) {
+    self.set(ParamValues {
+      lowpass_freq,
+      ..self.values
+    });
+  }
+
+  pub fn set_lowpass_res(&mut self, lowpass_res: f32) {
+    self.set(ParamValues {
+      lowpass_res,
+      ..self.values
+    });
+  }
+
+  pub fn set_reverb(&mut self, reverb: f32) {
+    self.set(ParamValues {
+      reverb,
+      ..self.values
+    });
+  }
+
+  pub fn set_decay(&mut self, decay: f32) {
+    self.set(ParamValues {
+      decay,
+      ..self.values
+    });
+  }
+
+  pub fn set_decay_low(&mut self, decay_low: f32) {
+    self.set(ParamValues {
+      decay_low,
+      ..self.values
+    });
+  }
+
+  pub fn set_decay_high(&mut self, decay_high: f32) {
+    self.set(ParamValues {
+      decay_high,
+      ..self.values
+    });
+  }
+
+  pub fn set_reverb_type(&mut self, reverb_type: ReverbType) {
+    self.set(ParamValues {
+      reverb_type,
+      ..self.values
+    });
+  }
+
+  pub fn set_routing(&mut self, routing: Routing) {
+    self.set(ParamValues {
+      routing,
+      ..self.values
+    });
+  }
+
+  pub fn set_stereo(&mut self, stereo: f32) {
+    self.set(ParamValues {
+      stereo,
+      ..self.values
+    });
+  }
+
+  pub fn set_duck(&mut self, duck: f32) {
+    self.set(ParamValues {
+      duck,
+      ..self.values
+    });
+  }
+
+  pub fn set_duck_source(&mut self, duck_source: DuckSource) {
+    self.set(ParamValues {
+      duck_source,
+      ..self.values
+    });
+  }
+
+  pub fn set_duck_target(&mut self, duck_target: DuckTarget) {
+    self.set(ParamValues {
+      duck_target,
+      ..self.values
+    });
+  }
+
+  pub fn set_duck_attack(&mut self, duck_attack: f32) {
+    self.set(ParamValues {
+      duck_attack,
+      ..self.values
+    });
+  }
+
+  pub fn set_duck_release(&mut self, duck_release: f32) {
+    self.set(ParamValues {
+      duck_release,
+      ..self.values
+    });
+  }
+
+  pub fn set_duck_hold(&mut self, duck_hold: f32) {
+    self.set(ParamValues {
+      duck_hold,
+      ..self.values
+    });
+  }
+
+  pub fn set_duck_depth(&mut self, duck_depth: f32) {
+    self.set(ParamValues {
+      duck_depth,
+      ..self.values
+    });
+  }
+
+  pub fn set_duck_detector(&mut self, duck_detector: DuckDetector) {
+    self.set(ParamValues {
+      duck_detector,
+      ..self.values
+    });
+  }
+
+  pub fn set_duck_highpass_freq(&mut self, duck_highpass_freq: f32) {
+    self.set(ParamValues {
+      duck_highpass_freq,
+      ..self.values
+    });
+  }
+
+  pub fn set_output_level(&mut self, output_level: f32) {
+    self.set(ParamValues {
+      output_level,
+      ..self.values
+    });
+  }
+
+  pub fn set_mix(&mut self, mix: f32) {
+    self.set(ParamValues { mix, ..self.values });
+  }
+
+  pub fn set_limiter(&mut self, limiter: bool) {
+    self.set(ParamValues {
+      limiter,
+      ..self.values
+    });
+  }
+
+  pub fn set_limiter_ceiling(&mut self, limiter_ceiling: f32) {
+    self.set(ParamValues {
+      limiter_ceiling,
+      ..self.values
+    });
+  }
+
+  pub fn set_limiter_release(&mut self, limiter_release: f32) {
+    self.set(ParamValues {
+      limiter_release,
+      ..self.values
+    });
+  }
+
+  pub fn set_limiter_link(&mut self, limiter_link: f32) {
+    self.set(ParamValues {
+      limiter_link,
+      ..self.values
+    });
+  }
+
+  pub fn set_limiter_true_peak(&mut self, limiter_true_peak: bool) {
+    self.set(ParamValues {
+      limiter_true_peak,
+      ..self.values
+    });
+  }
+
+  pub fn set_constant_latency(&mut self, constant_latency: bool) {
+    self.set(ParamValues {
+      constant_latency,
+      ..self.values
+    });
+  }
+
+  pub fn set_hold(&mut self, hold: bool) {
+    self.set(ParamValues {
+      hold,
+      ..self.values
+    });
+  }
+
+  pub fn set_freeze(&mut self, freeze: bool) {
+    self.set(ParamValues {
+      freeze,
+      ..self.values
+    });
+  }
+
+  pub fn set_size(&mut self, size: f32) {
+    self.set(ParamValues {
+      size,
+      ..self.values
+    });
+  }
+
+  pub fn set_predelay(&mut self, predelay: f32) {
+    self.set(ParamValues {
+      predelay,
+      ..self.values
+    });
+  }
+
+  pub fn set_damping(&mut self, damping: f32) {
+    self.set(ParamValues {
+      damping,
+      ..self.values
+    });
+  }
+
+  pub fn set_modulation_rate(&mut self, modulation_rate: f32) {
+    self.set(ParamValues {
+      modulation_rate,
+      ..self.values
+    });
+  }
+
+  pub fn set_modulation_depth(&mut self, modulation_depth: f32) {
+    self.set(ParamValues {
+      modulation_depth,
+      ..self.values
+    });
+  }
+
+  pub fn set_saturation_mode(&mut self, saturation_mode: SaturationMode) {
+    self.set(ParamValues {
+      saturation_mode,
+      ..self.values
+    });
+  }
+
+  pub fn set_drive(&mut self, drive: f32) {
+    self.set(ParamValues {
+      drive,
+      ..self.values
+    });
+  }
+
+  pub fn set_width(&mut self, width: f32) {
+    self.set(ParamValues {
+      width,
+      ..self.values
+    });
+  }
+
+  pub fn set_bias(&mut self, bias: f32) {
+    self.set(ParamValues {
+      bias,
+      ..self.values
+    });
+  }
+
+  pub fn set_shimmer_interval(&mut self, shimmer_interval: f32) {
+    self.set(ParamValues {
+      shimmer_interval,
+      ..self.values
+    });
+  }
+
+  pub fn set_shimmer(&mut self, shimmer: f32) {
+    self.set(ParamValues {
+      shimmer,
+      ..self.values
+    });
+  }
+
+  pub fn set_oversampling(&mut self, oversampling: Oversampling) {
+    self.set(ParamValues {
+      oversampling,
+      ..self.values
+    });
+  }
+
+  pub fn set_interpolation(&mut self, interpolation: Interpolation) {
+    self.set(ParamValues {
+      interpolation,
+      ..self.values
+    });
+  }
+
+  fn update(&mut self) {
+    let ParamValues {
+      input_level,
+      channel_mode,
+      time_mode,
+      head_mode,
+      time_link,
+      time_left,
+      time_right,
+      feedback,
+      head_feedback,
+      wow_and_flutter,
+      highpass_freq,
+      highpass_res,
+      lowpass_freq,
+      lowpass_res,
+      reverb,
+      decay,
+      decay_low,
+      decay_high,
+      reverb_type,
+      routing,
+      size,
+      predelay,
+      damping,
+      modulation_rate,
+      modulation_depth,
+      stereo,
+      duck,
+      duck_source,
+      duck_target,
+      duck_attack,
+      duck_release,
+      duck_hold,
+      duck_depth,
+      duck_detector,
+      duck_highpass_freq,
+      output_level,
+      mix,
+      limiter,
+      limiter_ceiling,
+      limiter_release,
+      limiter_link,
+      limiter_true_peak,
+      constant_latency,
+      hold,
+      freeze,
+      saturation_mode,
+      drive,
+      width,
+      bias,
+      shimmer_interval,
+      shimmer,
+      oversampling,
+      interpolation,
+    } = self.values;
     self.channel_mode = channel_mode;
     self.time_mode = time_mode;
+    self.head_mode = head_mode;
     self.highpass_res = highpass_res;
     self.lowpass_res = lowpass_res;
-    self.duck_threshold = (duck * MIN_DUCK_THRESHOLD).dbtoa();
+    self.reverb_type = reverb_type;
+    self.routing = routing;
+    self.duck_source = duck_source;
+    self.duck_target = duck_target;
+    self.duck_attack = duck_attack;
+    self.duck_release = duck_release;
+    self.duck_hold = duck_hold;
+    self.duck_depth = duck_depth;
+    self.duck_detector = duck_detector;
     self.limiter = limiter;
+    self.limiter_ceiling = limiter_ceiling;
+    self.limiter_release = limiter_release;
+    self.limiter_link = limiter_link;
+    self.limiter_true_peak = limiter_true_peak;
+    self.constant_latency = constant_latency;
+    self.saturation_mode = saturation_mode;
+    self.oversampling = oversampling;
+    self.interpolation = interpolation;
 
     let input_level = if hold { 0. } else { input_level.dbtoa() };
     let time_right = if time_link { time_left } else { time_right };
     let feedback = if hold { 1. } else { feedback };
+    let (engaged_heads, reverb_is_engaged) = get_head_mode(head_mode);
+    let head_gains = engaged_heads.map(|is_engaged| if is_engaged { 1. } else { 0. });
+    let head_feedback = match (hold, head_mode) {
+      (_, HeadMode::SingleHead) => [1.; 3],
+      (true, _) => {
+        // only the longest engaged head feeds back, so the held loop doesn't build up
+        let longest_head = engaged_heads.iter().rposition(|is_engaged| *is_engaged);
+        array::from_fn(|i| if Some(i) == longest_head { 1. } else { 0. })
+      }
+      (false, _) => head_feedback,
+    };
     let flutter_gain = if hold {
       0.
     } else {
       wow_and_flutter * wow_and_flutter * wow_and_flutter
     };
-    let decay = decay * 0.5;
+    let reverb = if reverb_is_engaged { reverb } else { 0. };
+    let dry_reverb_send = if engaged_heads.contains(&true) {
+      0.
+    } else {
+      1.
+    };
+    let duck_threshold = (duck * MIN_DUCK_THRESHOLD).dbtoa();
     let output_level = output_level.dbtoa();
     let filter_fader = if hold { 0. } else { 1. };
+    let freeze = if freeze { 1. } else { 0. };
+    let shimmer = if hold { 0. } else { shimmer };
 
     if self.is_initialized {
       self.input_level.set_target(input_level);
       self.time_left.set_target(time_left);
       self.time_right.set_target(time_right);
       self.feedback.set_target(feedback);
+      self
+        .head_gains
+        .iter_mut()
+        .zip(head_gains)
+        .for_each(|(smooth, gain)| smooth.set_target(gain));
+      self
+        .head_feedback
+        .iter_mut()
+        .zip(head_feedback)
+        .for_each(|(smooth, feedback)| smooth.set_target(feedback));
       self.flutter_gain.set_target(flutter_gain);
       self.highpass_freq.set_target(highpass_freq);
       self.lowpass_freq.set_target(lowpass_freq);
       self.reverb.set_target(reverb);
+      self.freeze.set_target(freeze);
       self.decay.set_target(decay);
+      self.decay_low.set_target(decay_low);
+      self.decay_high.set_target(decay_high);
+      self.size.set_target(size);
+      self.predelay.set_target(predelay);
+      self.damping.set_target(damping);
+      self.modulation_rate.set_target(modulation_rate);
+      self.modulation_depth.set_target(modulation_depth);
+      self.dry_reverb_send.set_target(dry_reverb_send);
       self.stereo.set_target(stereo);
+      self.duck_threshold.set_target(duck_threshold);
+      self.duck_highpass_freq.set_target(duck_highpass_freq);
       self.output_level.set_target(output_level);
       self.mix.set_target(mix);
       self.filter_fader.set_target(filter_fader);
+      self.drive.set_target(drive);
+      self.width.set_target(width);
+      self.bias.set_target(bias);
+      self.shimmer_interval.set_target(shimmer_interval);
+      self.shimmer.set_target(shimmer);
     } else {
       self.input_level.reset(input_level);
       self.time_left.reset(time_left);
       self.time_right.reset(time_right);
       self.feedback.reset(feedback);
+      self
+        .head_gains
+        .iter_mut()
+        .zip(head_gains)
+        .for_each(|(smooth, gain)| smooth.reset(gain));
+      self
+        .head_feedback
+        .iter_mut()
+        .zip(head_feedback)
+        .for_each(|(smooth, feedback)| smooth.reset(feedback));
       self.flutter_gain.reset(flutter_gain);
       self.highpass_freq.reset(highpass_freq);
       self.lowpass_freq.reset(lowpass_freq);
       self.reverb.reset(reverb);
+      self.freeze.reset(freeze);
       self.decay.reset(decay);
+      self.decay_low.reset(decay_low);
+      self.decay_high.reset(decay_high);
+      self.size.reset(size);
+      self.predelay.reset(predelay);
+      self.damping.reset(damping);
+      self.modulation_rate.reset(modulation_rate);
+      self.modulation_depth.reset(modulation_depth);
+      self.dry_reverb_send.reset(dry_reverb_send);
       self.stereo.reset(stereo);
+      self.duck_threshold.reset(duck_threshold);
+      self.duck_highpass_freq.reset(duck_highpass_freq);
       self.output_level.reset(output_level);
       self.mix.reset(mix);
       self.filter_fader.reset(filter_fader);
+      self.drive.reset(drive);
+      self.width.reset(width);
+      self.bias.reset(bias);
+      self.shimmer_interval.reset(shimmer_interval);
+      self.shimmer.reset(shimmer);
       self.is_initialized = true;
     }
   }
 
-  pub fn get_time(&mut self, time_mode: i32) -> (f32, f32) {
-    if time_mode == 0 {
-      (self.time_left.next(), self.time_right.next())
+  pub(crate) fn smoothed_params(&self) -> &SmoothedParams {
+    &self.smoothed_params
+  }
+
+  /// Applies the pending parameter changes and smooths the parameters for the next block.
+  pub(crate) fn next_block(&mut self, block_size: usize) {
+    if self.needs_update {
+      self.update();
+      self.needs_update = false;
+    }
+    let smoothed_params = &mut self.smoothed_params;
+
+    if self.time_mode == TimeMode::Repitch {
+      self
+        .time_left
+        .next_block(&mut smoothed_params.time_left[..block_size]);
+      self
+        .time_right
+        .next_block(&mut smoothed_params.time_right[..block_size]);
     } else {
-      (self.time_left.get_target(), self.time_right.get_target())
+      smoothed_params.time_left[..block_size].fill(self.time_left.get_target());
+      smoothed_params.time_right[..block_size].fill(self.time_right.get_target());
     }
+    self
+      .input_level
+      .next_block(&mut smoothed_params.input_level[..block_size]);
+    self
+      .feedback
+      .next_block(&mut smoothed_params.feedback[..block_size]);
+    self
+      .head_gains
+      .iter_mut()
+      .enumerate()
+      .for_each(|(head, smooth)| {
+        smoothed_params.head_gains[..block_size]
+          .iter_mut()
+          .for_each(|gains| gains[head] = smooth.next())
+      });
+    self
+      .head_feedback
+      .iter_mut()
+      .enumerate()
+      .for_each(|(head, smooth)| {
+        smoothed_params.head_feedback[..block_size]
+          .iter_mut()
+          .for_each(|feedback| feedback[head] = smooth.next())
+      });
+    self
+      .flutter_gain
+      .next_block(&mut smoothed_params.flutter_gain[..block_size]);
+    self
+      .highpass_freq
+      .next_block(&mut smoothed_params.highpass_freq[..block_size]);
+    self
+      .lowpass_freq
+      .next_block(&mut smoothed_params.lowpass_freq[..block_size]);
+    self
+      .reverb
+      .next_block(&mut smoothed_params.reverb[..block_size]);
+    self
+      .freeze
+      .next_block(&mut smoothed_params.freeze[..block_size]);
+    smoothed_params.decay = Self::skip_block(&mut self.decay, block_size);
+    smoothed_params.decay_low = Self::skip_block(&mut self.decay_low, block_size);
+    smoothed_params.decay_high = Self::skip_block(&mut self.decay_high, block_size);
+    self
+      .size
+      .next_block(&mut smoothed_params.size[..block_size]);
+    self
+      .predelay
+      .next_block(&mut smoothed_params.predelay[..block_size]);
+    smoothed_params.damping = Self::skip_block(&mut self.damping, block_size);
+    smoothed_params.modulation_rate = Self::skip_block(&mut self.modulation_rate, block_size);
+    smoothed_params.modulation_depth = Self::skip_block(&mut self.modulation_depth, block_size);
+    self
+      .dry_reverb_send
+      .next_block(&mut smoothed_params.dry_reverb_send[..block_size]);
+    self
+      .stereo
+      .next_block(&mut smoothed_params.stereo[..block_size]);
+    self
+      .output_level
+      .next_block(&mut smoothed_params.output_level[..block_size]);
+    self.mix.next_block(&mut smoothed_params.mix[..block_size]);
+    self
+      .duck_threshold
+      .next_block(&mut smoothed_params.duck_threshold[..block_size]);
+    smoothed_params.duck_highpass_freq = Self::skip_block(&mut self.duck_highpass_freq, block_size);
+    self
+      .filter_fader
+      .next_block(&mut smoothed_params.filter_fader[..block_size]);
+    smoothed_params.drive = Self::skip_block(&mut self.drive, block_size);
+    smoothed_params.width = Self::skip_block(&mut self.width, block_size);
+    smoothed_params.bias = Self::skip_block(&mut self.bias, block_size);
+    smoothed_params.shimmer_interval = Self::skip_block(&mut self.shimmer_interval, block_size);
+    smoothed_params.shimmer = Self::skip_block(&mut self.shimmer, block_size);
+  }
+
+  /// Advances the smoother to the end of the block and returns the last value.
+  fn skip_block(smooth: &mut impl Smoother, block_size: usize) -> f32 {
+    (0..block_size).fold(smooth.get_target(), |_, _| smooth.next())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{ParamValues, Params, SmoothedParams, TimeMode, MAX_BLOCK_SIZE};
+
+  #[test]
+  fn should_start_at_the_initial_values_without_smoothing() {
+    let mut params = Params::new(44100.);
+    params.set(ParamValues {
+      time_left: 500.,
+      time_link: false,
+      time_right: 300.,
+      ..Default::default()
+    });
+
+    params.next_block(4);
+    let smoothed_params = params.smoothed_params();
+    assert_eq!(smoothed_params.time_left[..4], [500.; 4]);
+    assert_eq!(smoothed_params.time_right[..4], [300.; 4]);
+  }
+
+  #[test]
+  fn should_apply_setters_on_the_next_block() {
+    let mut params = Params::new(44100.);
+    params.next_block(1);
+    params.set_time_mode(TimeMode::Fade);
+    params.set_time_left(1000.);
+    assert_eq!(params.values().time_left, 1000.);
+
+    params.next_block(2);
+    let smoothed_params = params.smoothed_params();
+    assert_eq!(smoothed_params.time_left[..2], [1000.; 2]);
+    assert_eq!(smoothed_params.time_right[..2], [1000.; 2]);
+  }
+
+  #[test]
+  fn should_smooth_the_filter_frequencies_for_each_sample() {
+    let mut params = Params::new(44100.);
+    params.next_block(1);
+    params.set_lowpass_freq(500.);
+    params.next_block(MAX_BLOCK_SIZE);
+
+    let lowpass_freq = params.smoothed_params().lowpass_freq;
+    assert!(lowpass_freq.windows(2).all(|x| x[1] < x[0]));
+    assert!(lowpass_freq[MAX_BLOCK_SIZE - 1] > 500.);
+  }
+
+  #[test]
+  fn should_smooth_the_reverb_size_and_predelay_for_each_sample() {
+    let mut params = Params::new(44100.);
+    params.next_block(1);
+    params.set_size(2.);
+    params.set_predelay(100.);
+    params.next_block(MAX_BLOCK_SIZE);
+
+    let SmoothedParams { size, predelay, .. } = params.smoothed_params();
+    assert!(size.windows(2).all(|x| x[1] > x[0]));
+    assert!(size[MAX_BLOCK_SIZE - 1] < 2.);
+    assert!(predelay.windows(2).all(|x| x[1] > x[0]));
+    assert!(predelay[MAX_BLOCK_SIZE - 1] < 100.);
+  }
+
+  #[test]
+  fn should_smooth_the_duck_threshold() {
+    let mut params = Params::new(44100.);
+    params.next_block(1);
+    assert_eq!(params.smoothed_params().duck_threshold[0], 1.);
+
+    params.set_duck(1.);
+    params.next_block(4);
+    let duck_threshold = params.smoothed_params().duck_threshold;
+    assert!(duck_threshold[..4].windows(2).all(|pair| pair[1] < pair[0]));
+    assert!(duck_threshold[3] > 0.9);
   }
 }
@@ -1,18 +1,48 @@
-use std::f32::consts::TAU;
+use {
+  crate::shared::stereo_block::LANES,
+  std::{
+    f32::consts::{LN_2, TAU},
+    simd::f32x8,
+  },
+};
 
 pub trait Smoother {
   fn reset(&mut self, target: f32);
 
+  fn set_sample_rate(&mut self, sample_rate: f32);
+
   fn set_target(&mut self, target: f32);
 
   fn get_target(&self) -> f32;
 
   fn next(&mut self) -> f32;
+
+  fn next_block(&mut self, output: &mut [f32]) {
+    output.iter_mut().for_each(|x| *x = self.next());
+  }
+}
+
+/// Returns the first eight powers of the factor, starting at the factor itself.
+fn get_powers(factor: f32) -> f32x8 {
+  f32x8::from_array(std::array::from_fn(|i| factor.powi(i as i32 + 1)))
+}
+
+/// Fills the block with a curve that moves the current value towards the target by the factor each sample, and returns the last value. <br />
+/// Sample n is `target + (current - target) * factor^n`, so eight samples can be calculated at once.
+fn fill_exponential_block(output: &mut [f32], current: f32, target: f32, powers: f32x8) -> f32 {
+  let mut difference = current - target;
+  for chunk in output.chunks_mut(LANES) {
+    let curve = f32x8::splat(target) + f32x8::splat(difference) * powers;
+    chunk.copy_from_slice(&curve.as_array()[..chunk.len()]);
+    difference *= powers[LANES - 1];
+  }
+  output.last().copied().unwrap_or(current)
 }
 
 pub struct LinearSmooth {
   current: f32,
   target: f32,
+  frequency: f32,
   factor: f32,
   step_size: f32,
   steps: usize,
@@ -24,6 +54,7 @@ impl LinearSmooth {
     Self {
       current: 0.,
       target: 0.,
+      frequency,
       factor: sample_rate.recip() * frequency,
       step_size: 0.,
       steps: (sample_rate / frequency) as usize,
@@ -38,6 +69,11 @@ impl Smoother for LinearSmooth {
     self.step_counter = 0;
   }
 
+  fn set_sample_rate(&mut self, sample_rate: f32) {
+    self.factor = sample_rate.recip() * self.frequency;
+    self.steps = (sample_rate / self.frequency) as usize;
+  }
+
   fn set_target(&mut self, target: f32) {
     if target != self.target {
       self.step_size = (target - self.current) * self.factor;
@@ -64,16 +100,21 @@ impl Smoother for LinearSmooth {
 pub struct ExponentialSmooth {
   current: f32,
   target: f32,
+  frequency: f32,
   b1: f32,
+  b1_powers: f32x8,
   is_active: bool,
 }
 
 impl ExponentialSmooth {
   pub fn new(sample_rate: f32, frequency: f32) -> Self {
+    let b1 = (-TAU * frequency * sample_rate.recip()).exp();
     Self {
       current: 0.,
       target: 0.,
-      b1: (-TAU * frequency * sample_rate.recip()).exp(),
+      frequency,
+      b1,
+      b1_powers: get_powers(b1),
       is_active: false,
     }
   }
@@ -85,6 +126,11 @@ impl Smoother for ExponentialSmooth {
     self.is_active = false;
   }
 
+  fn set_sample_rate(&mut self, sample_rate: f32) {
+    self.b1 = (-TAU * self.frequency * sample_rate.recip()).exp();
+    self.b1_powers = get_powers(self.b1);
+  }
+
   fn set_target(&mut self, target: f32) {
     self.target = target;
     self.is_active = self.current != self.target;
@@ -105,24 +151,46 @@ impl Smoother for ExponentialSmooth {
     }
     self.current
   }
+
+  fn next_block(&mut self, output: &mut [f32]) {
+    if self.is_active {
+      self.current = fill_exponential_block(output, self.current, self.target, self.b1_powers);
+      if (self.current - self.target).abs() <= f32::EPSILON {
+        self.current = self.target;
+        self.is_active = false;
+      }
+    } else {
+      output.fill(self.current);
+    }
+  }
 }
 
 pub struct LogarithmicSmooth {
   current: f32,
   target: f32,
+  smoothing_factor: f32,
   factor: f32,
+  decay_powers: f32x8,
   is_active: bool,
 }
 
 impl LogarithmicSmooth {
-  pub fn new(sample_rate: f32, factor: f32) -> Self {
+  pub fn new(sample_rate: f32, smoothing_factor: f32) -> Self {
+    let factor = (sample_rate * smoothing_factor).recip();
     Self {
       current: 0.,
       target: 0.,
-      factor: (sample_rate * factor).recip(),
+      smoothing_factor,
+      factor,
+      decay_powers: Self::get_decay_powers(factor),
       is_active: false,
     }
   }
+
+  /// The difference to the target is multiplied by `1 - ln(2) * factor` each sample.
+  fn get_decay_powers(factor: f32) -> f32x8 {
+    get_powers(1. - LN_2 * factor)
+  }
 }
 
 impl Smoother for LogarithmicSmooth {
@@ -131,6 +199,11 @@ impl Smoother for LogarithmicSmooth {
     self.is_active = false;
   }
 
+  fn set_sample_rate(&mut self, sample_rate: f32) {
+    self.factor = (sample_rate * self.smoothing_factor).recip();
+    self.decay_powers = Self::get_decay_powers(self.factor);
+  }
+
   fn set_target(&mut self, target: f32) {
     self.target = target;
     self.is_active = self.current != self.target;
@@ -152,11 +225,26 @@ impl Smoother for LogarithmicSmooth {
     }
     self.current
   }
+
+  fn next_block(&mut self, output: &mut [f32]) {
+    if self.is_active {
+      self.current = fill_exponential_block(output, self.current, self.target, self.decay_powers);
+      if (self.current - self.target).abs() <= f32::EPSILON {
+        self.current = self.target;
+        self.is_active = false;
+      }
+    } else {
+      output.fill(self.current);
+    }
+  }
 }
 
 #[cfg(test)]
 mod tests {
-  use crate::params::{smooth::LinearSmooth, Smoother};
+  use crate::params::{
+    smooth::{ExponentialSmooth, LinearSmooth, LogarithmicSmooth},
+    Smoother,
+  };
 
   #[test]
   fn should_smooth_linearly() {
@@ -178,4 +266,36 @@ mod tests {
     assert_eq!(linear_smooth.next(), 2.9802322e-8);
     assert_eq!(linear_smooth.next(), 0.0);
   }
+
+  #[test]
+  fn should_smooth_a_block_like_single_steps() {
+    let mut exponential_smooth = ExponentialSmooth::new(100., 7.);
+    let mut reference = ExponentialSmooth::new(100., 7.);
+    exponential_smooth.set_target(1.);
+    reference.set_target(1.);
+
+    let mut block = [0.; 64];
+    exponential_smooth.next_block(&mut block);
+    block
+      .into_iter()
+      .for_each(|x| assert!((x - reference.next()).abs() < 1e-6));
+
+    exponential_smooth.next_block(&mut block);
+    assert!(block.into_iter().all(|x| x == 1.));
+  }
+
+  #[test]
+  fn should_smooth_a_logarithmic_block_like_single_steps() {
+    let mut logarithmic_smooth = LogarithmicSmooth::new(1000., 0.01);
+    let mut reference = LogarithmicSmooth::new(1000., 0.01);
+    logarithmic_smooth.set_target(500.);
+    reference.set_target(500.);
+
+    let mut block = [0.; 29];
+    logarithmic_smooth.next_block(&mut block);
+    block
+      .into_iter()
+      .for_each(|x| assert!((x - reference.next()).abs() < 1e-3));
+    assert!((logarithmic_smooth.next() - reference.next()).abs() < 1e-3);
+  }
 }
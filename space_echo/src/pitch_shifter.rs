@@ -0,0 +1,165 @@
+use {
+  crate::shared::{
+    delay_line::{DelayLine, Interpolation},
+    float_ext::FloatExt,
+  },
+  std::{f32::consts::PI, simd::f32x2},
+};
+
+const WINDOW_TIME_IN_MS: f32 = 40.;
+/// The heads are half a window behind the input on average, so the unshifted signal is delayed by as much to stay aligned.
+const LATENCY_IN_MS: f32 = WINDOW_TIME_IN_MS * 0.5;
+
+/// Shifts the pitch with two read heads that sweep through a short delay line, half a window apart. <br />
+/// A head is faded out while it jumps back to the other end of the window.
+/// The windows of both heads always sum to one, so the shifter never adds gain to the feedback loop.
+pub struct PitchShifter {
+  delay_line_left: DelayLine,
+  delay_line_right: DelayLine,
+  dry_delay_line_left: DelayLine,
+  dry_delay_line_right: DelayLine,
+  sample_rate: f32,
+  interval: f32,
+  phase: f32,
+  phase_step: f32,
+}
+
+impl PitchShifter {
+  pub fn new(sample_rate: f32) -> Self {
+    let length = WINDOW_TIME_IN_MS.mstosamps(sample_rate) as usize + 2;
+    Self {
+      delay_line_left: DelayLine::new(length, sample_rate),
+      delay_line_right: DelayLine::new(length, sample_rate),
+      dry_delay_line_left: DelayLine::new(length, sample_rate),
+      dry_delay_line_right: DelayLine::new(length, sample_rate),
+      sample_rate,
+      interval: 0.,
+      phase: 0.,
+      phase_step: 0.,
+    }
+  }
+
+  pub fn set_sample_rate(&mut self, sample_rate: f32) {
+    self.delay_line_left.set_sample_rate(sample_rate);
+    self.delay_line_right.set_sample_rate(sample_rate);
+    self.dry_delay_line_left.set_sample_rate(sample_rate);
+    self.dry_delay_line_right.set_sample_rate(sample_rate);
+    self.sample_rate = sample_rate;
+    self.phase_step = self.get_phase_step();
+  }
+
+  pub fn reset(&mut self) {
+    self.delay_line_left.reset();
+    self.delay_line_right.reset();
+    self.dry_delay_line_left.reset();
+    self.dry_delay_line_right.reset();
+    self.phase = 0.;
+  }
+
+  /// Sets the pitch shift in semitones.
+  pub fn set_interval(&mut self, interval: f32) {
+    if interval != self.interval {
+      self.interval = interval;
+      self.phase_step = self.get_phase_step();
+    }
+  }
+
+  /// The latency of the shifter, which the delay time should be shortened by. <br />
+  /// It doesn't depend on the amount, so the shifter can be faded in without moving the repeats.
+  /// The delay lines are read right after the write, so they lag one sample less than the read time.
+  pub fn latency(&self) -> f32 {
+    LATENCY_IN_MS - 1000. / self.sample_rate
+  }
+
+  /// Delays the input that bypasses the shifter by its latency, so it stays aligned with the shifted feedback.
+  pub fn delay_dry(&mut self, input: f32x2) -> f32x2 {
+    self.dry_delay_line_left.write(input[0]);
+    self.dry_delay_line_right.write(input[1]);
+    f32x2::from_array([
+      self
+        .dry_delay_line_left
+        .read(LATENCY_IN_MS, Interpolation::Linear),
+      self
+        .dry_delay_line_right
+        .read(LATENCY_IN_MS, Interpolation::Linear),
+    ])
+  }
+
+  /// Blends the shifted signal into the input by the given amount. The unshifted signal is delayed by the latency, so both line up.
+  pub fn process(&mut self, input: f32x2, amount: f32) -> f32x2 {
+    self.delay_line_left.write(input[0]);
+    self.delay_line_right.write(input[1]);
+    let delayed_input = self.read(0.5);
+    if amount == 0. {
+      return delayed_input;
+    }
+
+    self.phase = (self.phase + self.phase_step).rem_euclid(1.);
+    let other_phase = (self.phase + 0.5).rem_euclid(1.);
+    let window = (self.phase * PI).fast_sin();
+    let window = window * window;
+
+    let shifted_output = self.read(self.phase) * f32x2::splat(window)
+      + self.read(other_phase) * f32x2::splat(1. - window);
+    delayed_input + (shifted_output - delayed_input) * f32x2::splat(amount)
+  }
+
+  fn read(&self, phase: f32) -> f32x2 {
+    let time = phase * WINDOW_TIME_IN_MS;
+    f32x2::from_array([
+      self.delay_line_left.read(time, Interpolation::Linear),
+      self.delay_line_right.read(time, Interpolation::Linear),
+    ])
+  }
+
+  /// The delay shrinks while shifting up, so the heads read the tape faster than it's written.
+  fn get_phase_step(&self) -> f32 {
+    let speed = (self.interval / 12.).exp2();
+    (1. - speed) * 1000. / (WINDOW_TIME_IN_MS * self.sample_rate)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::PitchShifter;
+  use std::{f32::consts::TAU, simd::f32x2};
+
+  fn count_zero_crossings(interval: f32) -> usize {
+    let mut pitch_shifter = PitchShifter::new(8000.);
+    pitch_shifter.set_interval(interval);
+
+    let output: Vec<f32> = (0..8000)
+      .map(|i| {
+        let input = (i as f32 / 8000. * 200. * TAU).sin();
+        pitch_shifter.process(f32x2::splat(input), 1.)[0]
+      })
+      .collect();
+    output[800..]
+      .windows(2)
+      .filter(|pair| pair[0] < 0. && pair[1] >= 0.)
+      .count()
+  }
+
+  #[test]
+  fn should_shift_by_the_interval() {
+    // 0.9 seconds of a 200 Hz sine
+    assert!(count_zero_crossings(0.).abs_diff(180) <= 2);
+    assert!(count_zero_crossings(12.).abs_diff(360) <= 8);
+    assert!(count_zero_crossings(-12.).abs_diff(90) <= 8);
+  }
+
+  #[test]
+  fn should_not_add_gain() {
+    let mut pitch_shifter = PitchShifter::new(8000.);
+    pitch_shifter.set_interval(7.);
+
+    assert!((0..8000).all(|_| {
+      let input = f32x2::splat(fastrand::f32() * 2. - 1.);
+      pitch_shifter
+        .process(input, 1.)
+        .to_array()
+        .iter()
+        .all(|output| output.abs() <= 1.)
+    }));
+  }
+}
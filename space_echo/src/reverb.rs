@@ -1,18 +1,40 @@
+mod dispersion;
 mod early_reflection;
 mod one_pole_filter;
+mod spring;
 
 use {
-  crate::shared::{
-    delay_line::{DelayLine, Interpolation},
-    mix::Mix,
-    phasor::Phasor,
-    random_oscillator::RandomOscillator,
+  crate::{
+    params::ReverbType,
+    shared::{
+      delay_line::{DelayLine, Interpolation},
+      float_ext::FloatExt,
+      mix::Mix,
+      phasor::Phasor,
+      random_oscillator::RandomOscillator,
+    },
+    variable_delay_read::Ramp,
   },
   early_reflection::EarlyReflection,
+  fastrand::Rng,
   one_pole_filter::OnePoleFilter,
-  std::simd::f32x4,
+  spring::Spring,
+  std::{f32::consts::FRAC_PI_2, simd::f32x4},
 };
 
+/// The size of [`Reverb::new`]. Sizes up to this scale the room up to twice the default size.
+pub const DEFAULT_MAX_REVERB_SIZE: f32 = 2.;
+pub const MAX_PREDELAY_IN_MS: f32 = 200.;
+pub const MAX_MODULATION_DEPTH_IN_MS: f32 = 5.;
+/// The time it takes to crossfade from one reverb type to the other.
+const REVERB_TYPE_FADE_TIME_IN_MS: f32 = 10.;
+/// The band below this frequency decays by the low decay multiplier.
+const LOW_CROSSOVER_FREQUENCY: f32 = 300.;
+/// The band above this frequency decays by the high decay multiplier.
+const HIGH_CROSSOVER_FREQUENCY: f32 = 3000.;
+const EARLY_REFLECTION_TIMES: [f32; 6] = [5.43216, 8.45346, 13.4367, 21.5463, 34.3876, 55.5437];
+const DELAY_TIMES: [f32; 4] = [60., 71.9345, 86.7545, 95.945];
+
 const MATRIX: [[f32; 4]; 4] = [
   [1.0, 1.0, 1.0, 1.0],
   [1.0, -1.0, 1.0, -1.0],
@@ -21,49 +43,291 @@ const MATRIX: [[f32; 4]; 4] = [
 ];
 
 pub struct Reverb {
+  max_size: f32,
+  predelay: f32,
+  predelay_line: [DelayLine; 2],
   early_reflections: [EarlyReflection; 6],
+  size: f32,
   time: [f32; 4],
+  modulation_depth: f32,
+  decay: f32,
+  freeze: f32,
+  decay_multipliers: (f32, f32),
+  decay_gains: [f32x4; 3],
+  low_crossover: OnePoleFilter,
+  high_crossover: OnePoleFilter,
   delay_line: [DelayLine; 4],
   one_pole_filter: OnePoleFilter,
   random_lfo: [RandomOscillator; 4],
   phasor: Phasor,
   mix: Mix,
+  interpolation: Interpolation,
+  spring: Spring,
+  reverb_type: ReverbType,
+  next_reverb_type: ReverbType,
+  previous_reverb_type: ReverbType,
+  reverb_type_fade: Ramp,
 }
 
 impl Reverb {
   pub fn new(sample_rate: f32) -> Self {
-    let delay_times = [60., 71.9345, 86.7545, 95.945];
+    Self::with_max_size(sample_rate, DEFAULT_MAX_REVERB_SIZE)
+  }
+
+  /// Allocates the delay lines for rooms up to the given size, where a size of one is the default room.
+  pub fn with_max_size(sample_rate: f32, max_size: f32) -> Self {
+    let predelay_length = MAX_PREDELAY_IN_MS.mstosamps(sample_rate) as usize + 1;
 
     Self {
-      early_reflections: [
-        EarlyReflection::new(sample_rate, 5.43216),
-        EarlyReflection::new(sample_rate, 8.45346),
-        EarlyReflection::new(sample_rate, 13.4367),
-        EarlyReflection::new(sample_rate, 21.5463),
-        EarlyReflection::new(sample_rate, 34.3876),
-        EarlyReflection::new(sample_rate, 55.5437),
+      max_size,
+      predelay: 0.,
+      predelay_line: [
+        DelayLine::new(predelay_length, sample_rate),
+        DelayLine::new(predelay_length, sample_rate),
       ],
-      time: delay_times,
-      delay_line: delay_times
-        .map(|time| DelayLine::new((sample_rate * time / 1000.) as usize + 1, sample_rate)),
+      early_reflections: EARLY_REFLECTION_TIMES
+        .map(|time| EarlyReflection::new(sample_rate, time, max_size)),
+      size: 1.,
+      time: DELAY_TIMES,
+      modulation_depth: 1.,
+      decay: 0.,
+      freeze: 0.,
+      decay_multipliers: (1., 1.),
+      decay_gains: [f32x4::splat(0.); 3],
+      low_crossover: OnePoleFilter::new(sample_rate, LOW_CROSSOVER_FREQUENCY),
+      high_crossover: OnePoleFilter::new(sample_rate, HIGH_CROSSOVER_FREQUENCY),
+      delay_line: DELAY_TIMES.map(|time| {
+        DelayLine::new(
+          (time * max_size + MAX_MODULATION_DEPTH_IN_MS).mstosamps(sample_rate) as usize + 1,
+          sample_rate,
+        )
+      }),
       one_pole_filter: OnePoleFilter::new(sample_rate, 6000.),
-      random_lfo: [RandomOscillator::new(); 4],
+      random_lfo: [0, 1, 2, 3].map(RandomOscillator::new),
       phasor: Phasor::new(sample_rate, 3.7),
       mix: Mix::new(),
+      interpolation: Interpolation::Linear,
+      spring: Spring::new(sample_rate),
+      reverb_type: ReverbType::Hall,
+      next_reverb_type: ReverbType::Hall,
+      previous_reverb_type: ReverbType::Hall,
+      reverb_type_fade: Ramp::new(sample_rate, 1000. / REVERB_TYPE_FADE_TIME_IN_MS),
+    }
+  }
+
+  /// Expects the sample rate to be at most the sample rate the reverb was created with, as the delay lines aren't resized.
+  pub fn set_sample_rate(&mut self, sample_rate: f32) {
+    self
+      .predelay_line
+      .iter_mut()
+      .for_each(|delay_line| delay_line.set_sample_rate(sample_rate));
+    self
+      .early_reflections
+      .iter_mut()
+      .for_each(|early_reflection| early_reflection.set_sample_rate(sample_rate));
+    self
+      .delay_line
+      .iter_mut()
+      .for_each(|delay_line| delay_line.set_sample_rate(sample_rate));
+    self.one_pole_filter.set_sample_rate(sample_rate);
+    self.low_crossover.set_sample_rate(sample_rate);
+    self.high_crossover.set_sample_rate(sample_rate);
+    self.phasor.set_sample_rate(sample_rate);
+    self.spring.set_sample_rate(sample_rate);
+    self.reverb_type_fade.set_sample_rate(sample_rate);
+  }
+
+  /// Restarts the modulation of the delay lines, with a random sequence for each that's derived from the seed.
+  pub fn seed(&mut self, seed: u64) {
+    let mut rng = Rng::with_seed(seed);
+    self.phasor.reset();
+    self
+      .random_lfo
+      .iter_mut()
+      .for_each(|random_lfo| random_lfo.seed(rng.u64(..)));
+  }
+
+  /// Switches between the feedback delay network and the spring tank. <br />
+  /// The tail of the previous type is crossfaded with the new type, which starts from silence.
+  /// A switch during a crossfade waits until it's finished.
+  pub fn set_reverb_type(&mut self, reverb_type: ReverbType) {
+    self.next_reverb_type = reverb_type;
+  }
+
+  /// Scales the early reflections and the delay lines of the feedback delay network. It's clamped to the max size.
+  pub fn set_size(&mut self, size: f32) {
+    let size = size.min(self.max_size);
+    if size == self.size {
+      return;
+    }
+    self.size = size;
+    self
+      .early_reflections
+      .iter_mut()
+      .for_each(|early_reflection| early_reflection.set_size(size));
+    self.time = DELAY_TIMES.map(|time| time * size);
+    self.update_decay_gains();
+  }
+
+  /// Delays the input of the reverb by the given time in milliseconds, up to [`MAX_PREDELAY_IN_MS`].
+  pub fn set_predelay(&mut self, predelay: f32) {
+    self.predelay = predelay.clamp(0., MAX_PREDELAY_IN_MS);
+  }
+
+  /// Sets the cutoff frequency of the lowpass filter in the feedback delay network.
+  pub fn set_damping(&mut self, freq: f32) {
+    self.one_pole_filter.set_freq(freq);
+  }
+
+  /// Sets the rate in hertz and the depth in milliseconds of the random delay time modulation.
+  pub fn set_modulation(&mut self, rate: f32, depth: f32) {
+    self.phasor.set_freq(rate);
+    let depth = depth.clamp(0., MAX_MODULATION_DEPTH_IN_MS);
+    if depth != self.modulation_depth {
+      self.modulation_depth = depth;
+      self.update_decay_gains();
     }
   }
 
+  /// Scales the reverb time of the hall below and above the crossover frequencies. One keeps the decay flat.
+  pub fn set_decay_multipliers(&mut self, low: f32, high: f32) {
+    if (low, high) != self.decay_multipliers {
+      self.decay_multipliers = (low, high);
+      self.update_decay_gains();
+    }
+  }
+
+  /// Fades from 0 to 1 to stop new input from entering the tank and to sustain the tail at unity gain.
+  pub fn set_freeze(&mut self, freeze: f32) {
+    self.freeze = freeze;
+  }
+
+  /// Sets how the modulated taps of the feedback delay network are read.
+  pub fn set_interpolation(&mut self, interpolation: Interpolation) {
+    self.interpolation = interpolation;
+  }
+
+  /// Clears the early reflections, the feedback delay network and the spring tank, so no tail is left.
+  /// A running crossfade between the reverb types is finished.
+  pub fn reset(&mut self) {
+    self
+      .predelay_line
+      .iter_mut()
+      .for_each(|delay_line| delay_line.reset());
+    self.reset_hall();
+    self.spring.reset();
+    self.reverb_type_fade.reset();
+    self.reverb_type = self.next_reverb_type;
+  }
+
+  fn reset_hall(&mut self) {
+    self
+      .early_reflections
+      .iter_mut()
+      .for_each(|early_reflection| early_reflection.reset());
+    self
+      .delay_line
+      .iter_mut()
+      .for_each(|delay_line| delay_line.reset());
+    self.one_pole_filter.reset();
+    self.low_crossover.reset();
+    self.high_crossover.reset();
+  }
+
+  /// The decay is the reverb time in seconds, the time it takes the tail to fall by 60 dB.
   pub fn process(&mut self, input: (f32, f32), reverb: f32, decay: f32) -> (f32, f32) {
+    self.process_send(input, input, reverb, decay, 1.)
+  }
+
+  /// Reverberates the send and mixes it with the dry signal, so the reverb can run in parallel to the signal it's mixed with.
+  /// The return gain only attenuates the reverberated signal.
+  pub fn process_send(
+    &mut self,
+    dry: (f32, f32),
+    send: (f32, f32),
+    reverb: f32,
+    decay: f32,
+    return_gain: f32,
+  ) -> (f32, f32) {
+    if decay != self.decay {
+      self.decay = decay;
+      self.update_decay_gains();
+    }
+
     if reverb > 0. {
-      let early_reflections_out = self.apply_early_reflections(input);
-      let reverb_out = self.apply_reverb_tail(early_reflections_out, decay);
+      let freeze = self.freeze;
+      let send_gain = 1. - freeze;
+      let predelay_out = self.apply_predelay((send.0 * send_gain, send.1 * send_gain));
+      let reverb_out = self.apply_reverb_types(predelay_out, freeze);
 
-      self.mix.process(input, reverb_out, reverb)
+      self.mix.process(
+        dry,
+        (reverb_out.0 * return_gain, reverb_out.1 * return_gain),
+        reverb,
+      )
     } else {
-      input
+      dry
     }
   }
 
+  /// Starts a crossfade when the reverb type has changed and no crossfade is running.
+  /// Both types process the input while they're crossfaded.
+  fn apply_reverb_types(&mut self, input: (f32, f32), freeze: f32) -> (f32, f32) {
+    if self.reverb_type_fade.is_finished() && self.next_reverb_type != self.reverb_type {
+      self.previous_reverb_type = self.reverb_type;
+      self.reverb_type = self.next_reverb_type;
+      match self.reverb_type {
+        ReverbType::Hall => self.reset_hall(),
+        ReverbType::Spring => self.spring.reset(),
+      }
+      self.reverb_type_fade.start();
+    }
+
+    if self.reverb_type_fade.is_finished() {
+      return self.apply_reverb_type(self.reverb_type, input, freeze);
+    }
+
+    let ramp = self.reverb_type_fade.process();
+    let window = (ramp * FRAC_PI_2).fast_cos();
+    let window = window * window;
+    let next = self.apply_reverb_type(self.reverb_type, input, freeze);
+    let previous = self.apply_reverb_type(self.previous_reverb_type, input, freeze);
+    (
+      next.0 + (previous.0 - next.0) * window,
+      next.1 + (previous.1 - next.1) * window,
+    )
+  }
+
+  fn apply_reverb_type(
+    &mut self,
+    reverb_type: ReverbType,
+    input: (f32, f32),
+    freeze: f32,
+  ) -> (f32, f32) {
+    match reverb_type {
+      ReverbType::Hall => {
+        let early_reflections_out = self.apply_early_reflections(input);
+        self.apply_reverb_tail(early_reflections_out, freeze)
+      }
+      ReverbType::Spring => self.spring.process(input, freeze),
+    }
+  }
+
+  fn apply_predelay(&mut self, input: (f32, f32)) -> (f32, f32) {
+    let [left, right] = &mut self.predelay_line;
+    let predelay_out = if self.predelay > 0. {
+      (
+        left.read(self.predelay, Interpolation::Linear),
+        right.read(self.predelay, Interpolation::Linear),
+      )
+    } else {
+      input
+    };
+    left.write(input.0);
+    right.write(input.1);
+    predelay_out
+  }
+
   fn apply_early_reflections(&mut self, input: (f32, f32)) -> (f32, f32) {
     let early_reflections_out = self
       .early_reflections
@@ -76,10 +340,10 @@ impl Reverb {
     )
   }
 
-  fn apply_reverb_tail(&mut self, input: (f32, f32), decay: f32) -> (f32, f32) {
+  fn apply_reverb_tail(&mut self, input: (f32, f32), freeze: f32) -> (f32, f32) {
     let delay_out = self.read_from_taps(input);
     let matrix_out = Self::apply_matrix(delay_out);
-    self.apply_absorption_and_write_to_taps(matrix_out, decay);
+    self.apply_absorption_and_write_to_taps(matrix_out, freeze);
 
     (delay_out[0], delay_out[1])
   }
@@ -89,32 +353,56 @@ impl Reverb {
 
     [
       self.delay_line[0].read(
-        self.time[0] + self.random_lfo[0].process(phase, 1.),
-        Interpolation::Linear,
+        self.time[0] + self.random_lfo[0].process(phase, 1.) * self.modulation_depth,
+        self.interpolation,
       ) + input.0,
       self.delay_line[1].read(
-        self.time[1] + self.random_lfo[1].process(phase, 1.),
-        Interpolation::Linear,
+        self.time[1] + self.random_lfo[1].process(phase, 1.) * self.modulation_depth,
+        self.interpolation,
       ) + input.1,
       self.delay_line[2].read(
-        self.time[2] + self.random_lfo[2].process(phase, 1.),
-        Interpolation::Linear,
+        self.time[2] + self.random_lfo[2].process(phase, 1.) * self.modulation_depth,
+        self.interpolation,
       ),
       self.delay_line[3].read(
-        self.time[3] + self.random_lfo[3].process(phase, 1.),
-        Interpolation::Linear,
+        self.time[3] + self.random_lfo[3].process(phase, 1.) * self.modulation_depth,
+        self.interpolation,
       ),
     ]
   }
 
-  fn apply_absorption_and_write_to_taps(&mut self, input: f32x4, decay: f32) {
+  /// While freezing, the absorbed and decayed signal is crossfaded with the unfiltered signal at unity gain. <br />
+  /// The filters keep running, so their state is up to date when the freeze is released.
+  fn apply_absorption_and_write_to_taps(&mut self, input: f32x4, freeze: f32) {
     let absorb_out = self.one_pole_filter.process(input);
+    let low = self.low_crossover.process(absorb_out);
+    let low_and_mid = self.high_crossover.process(absorb_out);
+    let [low_gain, mid_gain, high_gain] = self.decay_gains;
+    let decayed =
+      low * low_gain + (low_and_mid - low) * mid_gain + (absorb_out - low_and_mid) * high_gain;
+    let frozen = input * f32x4::splat(0.5);
+    let decay_out = decayed + (frozen - decayed) * f32x4::splat(freeze);
 
-    absorb_out
+    decay_out
       .to_array()
       .into_iter()
       .enumerate()
-      .for_each(|(i, x)| self.delay_line[i].write(x * decay));
+      .for_each(|(i, x)| self.delay_line[i].write(x));
+  }
+
+  /// Derives the gain of each line from its length, so every round trip loses the same amount per second. <br />
+  /// The gains include the 0.5 that makes the Hadamard matrix orthonormal.
+  fn update_decay_gains(&mut self) {
+    let (low_multiplier, high_multiplier) = self.decay_multipliers;
+    let loop_times = self
+      .time
+      .map(|time| (time + self.modulation_depth * 0.5) * 0.001);
+    self.decay_gains = [low_multiplier, 1., high_multiplier].map(|multiplier| {
+      f32x4::from_array(
+        loop_times.map(|loop_time| 0.5 * get_rt60_gain(loop_time, self.decay * multiplier)),
+      )
+    });
+    self.spring.set_decay(self.decay);
   }
 
   fn apply_matrix(input: [f32; 4]) -> f32x4 {
@@ -135,3 +423,217 @@ impl Reverb {
       .sum()
   }
 }
+
+/// Returns the gain that makes a loop of the given time in seconds fall by 60 dB in the given reverb time.
+fn get_rt60_gain(loop_time: f32, rt60: f32) -> f32 {
+  if rt60 > 0. {
+    0.001_f32.powf(loop_time / rt60)
+  } else {
+    0.
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{Interpolation, Reverb, ReverbType};
+  use std::f32::consts::TAU;
+
+  fn process_impulse(reverb: &mut Reverb, decay: f32, length: usize) -> Vec<(f32, f32)> {
+    (0..length)
+      .map(|i| {
+        let input = if i == 0 { 1. } else { 0. };
+        reverb.process((input, input), 1., decay)
+      })
+      .collect()
+  }
+
+  /// Measures the reverb time from the slope of the energy decay curve between -10 and -60 dB, which skips the build up of the early reflections.
+  fn measure_rt60(output: &[f32], sample_rate: f32) -> f32 {
+    let mut energy_decay: Vec<f32> = output
+      .iter()
+      .rev()
+      .scan(0., |sum, x| {
+        *sum += x * x;
+        Some(*sum)
+      })
+      .collect();
+    energy_decay.reverse();
+    let total_energy = energy_decay[0];
+    let find_level = |db: f32| {
+      energy_decay
+        .iter()
+        .position(|energy| 10. * (energy / total_energy).log10() <= db)
+        .unwrap()
+    };
+
+    1.2 * (find_level(-60.) - find_level(-10.)) as f32 / sample_rate
+  }
+
+  /// A one pole lowpass filter at 48 kHz.
+  fn lowpass(input: &[f32], freq: f32) -> Vec<f32> {
+    let b1 = (-TAU * freq / 48000.).exp();
+    input
+      .iter()
+      .scan(0., |z, x| {
+        *z = x * (1. - b1) + *z * b1;
+        Some(*z)
+      })
+      .collect()
+  }
+
+  fn highpass(input: &[f32], freq: f32) -> Vec<f32> {
+    input
+      .iter()
+      .zip(lowpass(input, freq))
+      .map(|(x, lowpassed)| x - lowpassed)
+      .collect()
+  }
+
+  fn create_reverb(sample_rate: f32, size: f32) -> Reverb {
+    let mut reverb = Reverb::new(sample_rate);
+    reverb.set_size(size);
+    reverb.set_damping(20000.);
+    reverb.set_modulation(3.7, 0.);
+    reverb
+  }
+
+  #[test]
+  fn should_delay_the_reverb_by_the_predelay() {
+    let mut reverb = Reverb::new(1000.);
+    reverb.set_predelay(50.);
+
+    let output = process_impulse(&mut reverb, 1., 100);
+    assert_eq!(output.iter().position(|x| x.0 != 0.), Some(50));
+  }
+
+  #[test]
+  fn should_spread_the_reflections_with_the_size() {
+    let get_first_reflection = |size: f32| {
+      let mut reverb = Reverb::new(1000.);
+      reverb.set_size(size);
+      (0..200)
+        .map(|i| reverb.process((if i == 0 { 1. } else { 0. }, 0.), 1., 1.))
+        .position(|x| x.1 != 0.)
+        .unwrap()
+    };
+
+    assert_eq!(get_first_reflection(1.), 56);
+    assert_eq!(get_first_reflection(2.), 111);
+  }
+
+  #[test]
+  fn should_crossfade_the_tail_when_switching_the_reverb_type() {
+    let mut hall = Reverb::new(8000.);
+    let mut switched = Reverb::new(8000.);
+    (0..1000).for_each(|_| {
+      let x = fastrand::f32() * 2. - 1.;
+      hall.process((x, x), 1., 2.);
+      switched.process((x, x), 1., 2.);
+    });
+
+    switched.set_reverb_type(ReverbType::Spring);
+    let (hall_output, switched_output): (Vec<f32>, Vec<f32>) = (0..160)
+      .map(|_| {
+        (
+          hall.process((0., 0.), 1., 2.).0,
+          switched.process((0., 0.), 1., 2.).0,
+        )
+      })
+      .unzip();
+    let get_energy = |output: &[f32]| output.iter().map(|x| x * x).sum::<f32>();
+
+    // the hall tail fades out over 10 ms, while the spring starts from silence
+    assert!((switched_output[0] - hall_output[0]).abs() <= hall_output[0].abs() * 0.01);
+    assert!(get_energy(&switched_output[..20]) > get_energy(&hall_output[..20]) * 0.5);
+    assert!(switched_output[90..].iter().all(|x| *x == 0.));
+  }
+
+  #[test]
+  fn should_decay_by_the_reverb_time() {
+    [
+      (8000., 1., 1.),
+      (8000., 0.5, 2.),
+      (16000., 1., 0.5),
+      (16000., 2., 1.),
+    ]
+    .into_iter()
+    .for_each(|(sample_rate, decay, size)| {
+      let mut reverb = create_reverb(sample_rate, size);
+      let output: Vec<f32> =
+        process_impulse(&mut reverb, decay, (sample_rate * decay * 2.) as usize)
+          .into_iter()
+          .map(|x| x.0)
+          .collect();
+
+      let rt60 = measure_rt60(&output, sample_rate);
+      assert!(
+        (rt60 / decay - 1.).abs() < 0.15,
+        "expected {} seconds at {} Hz and size {}, measured {}",
+        decay,
+        sample_rate,
+        size,
+        rt60
+      );
+    });
+  }
+
+  #[test]
+  fn should_scale_the_decay_of_the_low_and_high_frequencies() {
+    let get_band_rt60s = |low: f32, high: f32| {
+      let mut reverb = create_reverb(48000., 1.);
+      reverb.set_decay_multipliers(low, high);
+      let output: Vec<f32> = process_impulse(&mut reverb, 1., 144000)
+        .into_iter()
+        .map(|x| x.0)
+        .collect();
+
+      let low_band = lowpass(&lowpass(&output, 50.), 50.);
+      let high_band = highpass(&highpass(&output, 12000.), 12000.);
+      (
+        measure_rt60(&low_band, 48000.),
+        measure_rt60(&high_band, 48000.),
+      )
+    };
+
+    let (flat_low, flat_high) = get_band_rt60s(1., 1.);
+    let (low, high) = get_band_rt60s(2., 0.5);
+    assert!(low > flat_low * 1.5);
+    assert!(high < flat_high * 0.75);
+  }
+
+  #[test]
+  fn should_sustain_the_tail_when_frozen() {
+    [
+      Interpolation::Linear,
+      Interpolation::Cubic,
+      Interpolation::Spline,
+    ]
+    .into_iter()
+    .for_each(|interpolation| {
+      [1., 5.].into_iter().for_each(|modulation_depth| {
+        let mut reverb = Reverb::new(8000.);
+        reverb.set_interpolation(interpolation);
+        reverb.set_modulation(3.7, modulation_depth);
+        let mut get_rms = |length: usize, freeze: f32| {
+          let energy: f32 = (0..length)
+            .map(|_| {
+              let x = fastrand::f32() * 2. - 1.;
+              reverb.set_freeze(freeze);
+              reverb.process((x, x), 1., 1.).0
+            })
+            .map(|x| x * x)
+            .sum();
+          (energy / length as f32).sqrt()
+        };
+
+        get_rms(4000, 0.);
+        get_rms(4000, 1.);
+        let start = get_rms(8000, 1.);
+        get_rms(16000, 1.);
+        let end = get_rms(8000, 1.);
+        assert!(end < start * 1.05);
+        assert!(end > start * 0.5);
+      });
+    });
+  }
+}
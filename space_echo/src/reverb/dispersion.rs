@@ -0,0 +1,73 @@
+use std::simd::f32x2;
+
+const STAGES: usize = 40;
+const MAX_STRETCH: usize = 32;
+const ALLPASS_COEFFICIENT: f32 = 0.6;
+/// The frequency below which the chirps of the spring are heard.
+const TRANSITION_FREQUENCY: f32 = 4300.;
+
+/// A cascade of stretched allpass filters, which delays each frequency by a different amount. <br />
+/// The stretch of K samples folds the dispersion K times, so it lands below the transition frequency.
+pub struct Dispersion {
+  buffers: [[f32x2; MAX_STRETCH]; STAGES],
+  stretch: usize,
+  index: usize,
+}
+
+impl Dispersion {
+  pub fn new(sample_rate: f32) -> Self {
+    Self {
+      buffers: [[f32x2::splat(0.); MAX_STRETCH]; STAGES],
+      stretch: Self::get_stretch(sample_rate),
+      index: 0,
+    }
+  }
+
+  pub fn set_sample_rate(&mut self, sample_rate: f32) {
+    self.stretch = Self::get_stretch(sample_rate);
+    self.reset();
+  }
+
+  pub fn reset(&mut self) {
+    self.buffers = [[f32x2::splat(0.); MAX_STRETCH]; STAGES];
+    self.index = 0;
+  }
+
+  pub fn process(&mut self, input: f32x2) -> f32x2 {
+    let coefficient = f32x2::splat(ALLPASS_COEFFICIENT);
+    let index = self.index;
+
+    let output = self.buffers.iter_mut().fold(input, |x, buffer| {
+      // each slot of the buffer is overwritten once every stretch samples
+      let delayed = buffer[index];
+      let w = x - coefficient * delayed;
+      buffer[index] = w;
+      coefficient * w + delayed
+    });
+    self.index = (index + 1) % self.stretch;
+    output
+  }
+
+  fn get_stretch(sample_rate: f32) -> usize {
+    ((sample_rate / (2. * TRANSITION_FREQUENCY)).round() as usize).clamp(1, MAX_STRETCH)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::Dispersion;
+  use std::simd::f32x2;
+
+  #[test]
+  fn should_smear_an_impulse_without_changing_its_energy() {
+    let mut dispersion = Dispersion::new(44100.);
+    let output: Vec<f32> = (0..44100)
+      .map(|i| dispersion.process(f32x2::splat(if i == 0 { 1. } else { 0. }))[0])
+      .collect();
+
+    let energy: f32 = output.iter().map(|x| x * x).sum();
+    let peak = output.iter().fold(0_f32, |peak, x| peak.max(x.abs()));
+    assert!((energy - 1.).abs() < 0.01);
+    assert!(peak < 0.5);
+  }
+}
@@ -1,18 +1,39 @@
-use crate::shared::delay_line::{DelayLine, Interpolation};
+use crate::shared::{
+  delay_line::{DelayLine, Interpolation},
+  float_ext::FloatExt,
+};
 
 pub struct EarlyReflection {
+  base_time_in_ms: f32,
   time_in_ms: f32,
   delay_line: DelayLine,
 }
 
 impl EarlyReflection {
-  pub fn new(sample_rate: f32, time_in_ms: f32) -> Self {
+  /// Allocates the delay line for the time scaled by the largest size.
+  pub fn new(sample_rate: f32, time_in_ms: f32, max_size: f32) -> Self {
     Self {
+      base_time_in_ms: time_in_ms,
       time_in_ms,
-      delay_line: DelayLine::new((time_in_ms * sample_rate) as usize, sample_rate),
+      delay_line: DelayLine::new(
+        (time_in_ms * max_size).mstosamps(sample_rate) as usize + 1,
+        sample_rate,
+      ),
     }
   }
 
+  pub fn set_sample_rate(&mut self, sample_rate: f32) {
+    self.delay_line.set_sample_rate(sample_rate);
+  }
+
+  pub fn set_size(&mut self, size: f32) {
+    self.time_in_ms = self.base_time_in_ms * size;
+  }
+
+  pub fn reset(&mut self) {
+    self.delay_line.reset();
+  }
+
   pub fn process(&mut self, input: (f32, f32)) -> (f32, f32) {
     let added_channels = input.0 + input.1;
     let subtracted_channels = input.0 - input.1;
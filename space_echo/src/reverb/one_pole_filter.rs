@@ -2,22 +2,46 @@ use std::{f32::consts::TAU, simd::f32x4};
 
 pub struct OnePoleFilter {
   z: f32x4,
+  freq: f32,
+  t: f32,
   b1: f32x4,
   a0: f32x4,
 }
 
 impl OnePoleFilter {
   pub fn new(sample_rate: f32, freq: f32) -> Self {
-    let t = sample_rate.recip() * -TAU;
-    let b1 = f32x4::splat((freq * t).exp());
-
-    Self {
+    let mut one_pole_filter = Self {
       z: f32x4::splat(0.),
-      b1,
-      a0: f32x4::splat(1.0) - b1,
+      freq,
+      t: 0.,
+      b1: f32x4::splat(0.),
+      a0: f32x4::splat(1.),
+    };
+    one_pole_filter.set_sample_rate(sample_rate);
+    one_pole_filter
+  }
+
+  pub fn set_sample_rate(&mut self, sample_rate: f32) {
+    self.t = sample_rate.recip() * -TAU;
+    self.set_coefficients();
+  }
+
+  pub fn set_freq(&mut self, freq: f32) {
+    if freq != self.freq {
+      self.freq = freq;
+      self.set_coefficients();
     }
   }
 
+  pub fn reset(&mut self) {
+    self.z = f32x4::splat(0.);
+  }
+
+  fn set_coefficients(&mut self) {
+    self.b1 = f32x4::splat((self.freq * self.t).exp());
+    self.a0 = f32x4::splat(1.0) - self.b1;
+  }
+
   pub fn process(&mut self, input: f32x4) -> f32x4 {
     self.z = input * self.a0 + self.z * self.b1;
     self.z
@@ -0,0 +1,196 @@
+use {
+  super::{dispersion::Dispersion, get_rt60_gain},
+  crate::shared::delay_line::{DelayLine, Interpolation},
+  std::{
+    f32::consts::{PI, TAU},
+    simd::{f32x2, num::SimdFloat},
+  },
+};
+
+/// The round trip time of the left and the right spring.
+const DELAY_TIMES: [f32; 2] = [41.3, 47.9];
+const LOWPASS_FREQUENCY: f32 = 4300.;
+const BOING_FREQUENCY: f32 = 110.;
+const BOING_BANDWIDTH: f32 = 12.;
+const BOING_GAIN: f32 = 6.;
+
+/// A tank of two springs. Each round trip runs through a dispersive allpass cascade, so every repeat chirps. <br />
+/// Transients excite a low resonance before they reach the springs, like the boing of a tank that's knocked.
+pub struct Spring {
+  dispersion: Dispersion,
+  delay_line_left: DelayLine,
+  delay_line_right: DelayLine,
+  feedback_gain: f32x2,
+  lowpass_b1: f32,
+  lowpass_z: f32x2,
+  fast_envelope: f32,
+  slow_envelope: f32,
+  fast_attack: f32,
+  fast_release: f32,
+  slow_release: f32,
+  boing_b1: f32,
+  boing_b2: f32,
+  boing_gain: f32,
+  boing_z: [f32; 2],
+}
+
+impl Spring {
+  pub fn new(sample_rate: f32) -> Self {
+    let length = (DELAY_TIMES[1] * sample_rate / 1000.) as usize + 2;
+    let mut spring = Self {
+      dispersion: Dispersion::new(sample_rate),
+      delay_line_left: DelayLine::new(length, sample_rate),
+      delay_line_right: DelayLine::new(length, sample_rate),
+      feedback_gain: f32x2::splat(0.),
+      lowpass_b1: 0.,
+      lowpass_z: f32x2::splat(0.),
+      fast_envelope: 0.,
+      slow_envelope: 0.,
+      fast_attack: 0.,
+      fast_release: 0.,
+      slow_release: 0.,
+      boing_b1: 0.,
+      boing_b2: 0.,
+      boing_gain: 0.,
+      boing_z: [0.; 2],
+    };
+    spring.set_sample_rate(sample_rate);
+    spring
+  }
+
+  pub fn set_sample_rate(&mut self, sample_rate: f32) {
+    self.dispersion.set_sample_rate(sample_rate);
+    self.delay_line_left.set_sample_rate(sample_rate);
+    self.delay_line_right.set_sample_rate(sample_rate);
+    self.lowpass_b1 = (-TAU * LOWPASS_FREQUENCY / sample_rate).exp();
+    self.fast_attack = Self::get_envelope_coefficient(1., sample_rate);
+    self.fast_release = Self::get_envelope_coefficient(20., sample_rate);
+    self.slow_release = Self::get_envelope_coefficient(80., sample_rate);
+
+    let radius = (-PI * BOING_BANDWIDTH / sample_rate).exp();
+    let omega = TAU * BOING_FREQUENCY / sample_rate;
+    self.boing_b1 = 2. * radius * omega.cos();
+    self.boing_b2 = -radius * radius;
+    // scales the resonance peak to unity gain
+    self.boing_gain = (1. - radius) * 2. * omega.sin();
+  }
+
+  pub fn reset(&mut self) {
+    self.dispersion.reset();
+    self.delay_line_left.reset();
+    self.delay_line_right.reset();
+    self.lowpass_z = f32x2::splat(0.);
+    self.fast_envelope = 0.;
+    self.slow_envelope = 0.;
+    self.boing_z = [0.; 2];
+  }
+
+  /// Sets the reverb time in seconds from the round trip time of each spring.
+  pub fn set_decay(&mut self, decay: f32) {
+    self.feedback_gain =
+      f32x2::from_array(DELAY_TIMES.map(|time| get_rt60_gain(time * 0.001, decay)));
+  }
+
+  /// The freeze fades the feedback of the springs to unity gain, so only the loop lowpass filter damps the tail.
+  pub fn process(&mut self, input: (f32, f32), freeze: f32) -> (f32, f32) {
+    let input = f32x2::from_array([input.0, input.1]);
+    let boing = self.get_boing(input);
+    let feedback = f32x2::from_array([
+      self
+        .delay_line_left
+        .read(DELAY_TIMES[0], Interpolation::Linear),
+      self
+        .delay_line_right
+        .read(DELAY_TIMES[1], Interpolation::Linear),
+    ]);
+
+    // the input is halved to match the level of the hall
+    let spring_input =
+      (input + f32x2::splat(boing)) * f32x2::splat(0.5) + feedback * self.get_feedback_gain(freeze);
+    let dispersion_output = self.dispersion.process(spring_input);
+    self.lowpass_z = dispersion_output * f32x2::splat(1. - self.lowpass_b1)
+      + self.lowpass_z * f32x2::splat(self.lowpass_b1);
+
+    self.delay_line_left.write(self.lowpass_z[0]);
+    self.delay_line_right.write(self.lowpass_z[1]);
+    (self.lowpass_z[0], self.lowpass_z[1])
+  }
+
+  fn get_feedback_gain(&self, freeze: f32) -> f32x2 {
+    self.feedback_gain + (f32x2::splat(1.) - self.feedback_gain) * f32x2::splat(freeze)
+  }
+
+  /// Rings the resonance by how much faster the fast envelope rises than the slow one.
+  fn get_boing(&mut self, input: f32x2) -> f32 {
+    let level = input.abs().reduce_max();
+    let fast_coefficient = if level > self.fast_envelope {
+      self.fast_attack
+    } else {
+      self.fast_release
+    };
+    self.fast_envelope = level + (self.fast_envelope - level) * fast_coefficient;
+    let slow_coefficient = if level > self.slow_envelope {
+      self.fast_release
+    } else {
+      self.slow_release
+    };
+    self.slow_envelope = level + (self.slow_envelope - level) * slow_coefficient;
+    let transient = (self.fast_envelope - self.slow_envelope).max(0.);
+
+    let boing = transient * BOING_GAIN * self.boing_gain
+      + self.boing_z[0] * self.boing_b1
+      + self.boing_z[1] * self.boing_b2;
+    self.boing_z = [boing, self.boing_z[0]];
+    boing
+  }
+
+  fn get_envelope_coefficient(time_in_ms: f32, sample_rate: f32) -> f32 {
+    (-1. / (time_in_ms * 0.001 * sample_rate)).exp()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::Spring;
+  use std::simd::f32x2;
+
+  fn get_energy(spring: &mut Spring, input: impl Fn(usize) -> f32, range: (usize, usize)) -> f32 {
+    (0..range.1)
+      .map(|i| {
+        let x = input(i);
+        spring.process((x, x), 0.).0
+      })
+      .skip(range.0)
+      .map(|x| x * x)
+      .sum()
+  }
+
+  #[test]
+  fn should_decay() {
+    let mut spring = Spring::new(8000.);
+    spring.set_decay(1.);
+    let impulse = |i: usize| if i == 0 { 1. } else { 0. };
+    let energies: Vec<f32> = (0..4)
+      .map(|second| {
+        spring.reset();
+        get_energy(&mut spring, impulse, (second * 8000, (second + 1) * 8000))
+      })
+      .collect();
+
+    assert!(energies.iter().all(|energy| energy.is_finite()));
+    assert!(energies.windows(2).all(|pair| pair[1] < pair[0]));
+  }
+
+  #[test]
+  fn should_boing_on_transients_only() {
+    let mut spring = Spring::new(8000.);
+    let boing: Vec<f32> = (0..16000)
+      .map(|_| spring.get_boing(f32x2::splat(0.5)).abs())
+      .collect();
+
+    let onset = boing[..800].iter().fold(0_f32, |peak, x| peak.max(*x));
+    let sustain = boing[12000..].iter().fold(0_f32, |peak, x| peak.max(*x));
+    assert!(onset > 0.1);
+    assert!(sustain < onset * 0.01);
+  }
+}
@@ -1,14 +1,101 @@
-use std::simd::{f32x2, StdFloat};
+mod hysteresis;
+use {
+  crate::params::SaturationMode,
+  hysteresis::Hysteresis,
+  std::{
+    f32::consts::TAU,
+    simd::{f32x2, StdFloat},
+  },
+};
 
-pub struct Saturation;
+const DC_BLOCKER_FREQ: f32 = 10.;
+
+pub struct Saturation {
+  hysteresis: [Hysteresis; 2],
+  drive: f32,
+  width: f32,
+  bias: f32,
+  dc_blocker_b1: f32,
+  dc_blocker_x: f32x2,
+  dc_blocker_y: f32x2,
+}
 
 impl Saturation {
-  pub fn process(input: f32x2, mix: f32) -> f32x2 {
+  pub fn new(sample_rate: f32) -> Self {
+    Self {
+      hysteresis: [Hysteresis::new(sample_rate), Hysteresis::new(sample_rate)],
+      drive: 0.5,
+      width: 0.5,
+      bias: 0.,
+      dc_blocker_b1: Self::get_dc_blocker_b1(sample_rate),
+      dc_blocker_x: f32x2::splat(0.),
+      dc_blocker_y: f32x2::splat(0.),
+    }
+  }
+
+  pub fn set_sample_rate(&mut self, sample_rate: f32) {
+    self
+      .hysteresis
+      .iter_mut()
+      .for_each(|hysteresis| hysteresis.set_sample_rate(sample_rate));
+    self.dc_blocker_b1 = Self::get_dc_blocker_b1(sample_rate);
+  }
+
+  pub fn reset(&mut self) {
+    self
+      .hysteresis
+      .iter_mut()
+      .for_each(|hysteresis| hysteresis.reset());
+    self.dc_blocker_x = f32x2::splat(0.);
+    self.dc_blocker_y = f32x2::splat(0.);
+  }
+
+  /// Sets the tape settings of the hysteresis mode. The bias shifts the signal off center to add even harmonics.
+  pub fn set_hysteresis(&mut self, drive: f32, width: f32, bias: f32) {
+    self.drive = drive;
+    self.width = width;
+    self.bias = bias;
+  }
+
+  /// The cheap mode blends in a static curve by the given mix, the hysteresis mode ignores the mix.
+  pub fn process(&mut self, input: f32x2, mode: SaturationMode, mix: f32) -> f32x2 {
+    match mode {
+      SaturationMode::Cheap => Self::process_cheap(input, mix),
+      SaturationMode::Hysteresis => self.process_hysteresis(input),
+    }
+  }
+
+  fn process_cheap(input: f32x2, mix: f32) -> f32x2 {
     let mix = (mix * mix).clamp(0., 1.);
 
     input + (Self::saturate(input) - input) * f32x2::splat(mix)
   }
 
+  fn process_hysteresis(&mut self, input: f32x2) -> f32x2 {
+    let Self {
+      drive, width, bias, ..
+    } = *self;
+    let biased_input = input + f32x2::splat(bias * 0.5);
+    let [left, right] = &mut self.hysteresis;
+    let hysteresis_output = f32x2::from_array([
+      left.process(biased_input[0], drive, width),
+      right.process(biased_input[1], drive, width),
+    ]);
+
+    self.block_dc(hysteresis_output)
+  }
+
+  fn block_dc(&mut self, input: f32x2) -> f32x2 {
+    self.dc_blocker_y =
+      input - self.dc_blocker_x + self.dc_blocker_y * f32x2::splat(self.dc_blocker_b1);
+    self.dc_blocker_x = input;
+    self.dc_blocker_y
+  }
+
+  fn get_dc_blocker_b1(sample_rate: f32) -> f32 {
+    (-TAU * DC_BLOCKER_FREQ / sample_rate).exp()
+  }
+
   fn saturate(x: f32x2) -> f32x2 {
     x / (x * x + f32x2::splat(1.)).sqrt()
   }
@@ -0,0 +1,168 @@
+/* Jiles-Atherton magnetic hysteresis, solved with the second order Runge-Kutta method */
+
+const ALPHA: f32 = 1.6e-3;
+const COERCIVITY: f32 = 0.27;
+const MAX_MAGNETISATION: f32 = 20.;
+
+pub struct Hysteresis {
+  t: f32,
+  m_n1: f32,
+  h_n1: f32,
+  h_d_n1: f32,
+  m_s: f32,
+  one_over_a: f32,
+  c: f32,
+  makeup_gain: f32,
+  prev_drive: f32,
+  prev_width: f32,
+}
+
+impl Hysteresis {
+  pub fn new(sample_rate: f32) -> Self {
+    let mut hysteresis = Self {
+      t: sample_rate.recip(),
+      m_n1: 0.,
+      h_n1: 0.,
+      h_d_n1: 0.,
+      m_s: 1.,
+      one_over_a: 1.,
+      c: 0.,
+      makeup_gain: 1.,
+      prev_drive: -1.,
+      prev_width: -1.,
+    };
+    hysteresis.set_coefficients(0.5, 0.5);
+    hysteresis
+  }
+
+  pub fn set_sample_rate(&mut self, sample_rate: f32) {
+    self.t = sample_rate.recip();
+  }
+
+  pub fn reset(&mut self) {
+    self.m_n1 = 0.;
+    self.h_n1 = 0.;
+    self.h_d_n1 = 0.;
+  }
+
+  /// Drive sets how hard the tape is magnetised and width sets how wide the hysteresis loop is. Both range from 0 to 1.
+  pub fn process(&mut self, input: f32, drive: f32, width: f32) -> f32 {
+    if drive != self.prev_drive || width != self.prev_width {
+      self.set_coefficients(drive, width);
+    }
+
+    let h = input;
+    let h_d = self.differentiate(h);
+    let m = self.solve(h, h_d);
+
+    self.h_n1 = h;
+    self.h_d_n1 = h_d;
+    self.m_n1 = if m.is_finite() {
+      m.clamp(-MAX_MAGNETISATION, MAX_MAGNETISATION)
+    } else {
+      0.
+    };
+
+    self.m_n1 * self.makeup_gain
+  }
+
+  fn set_coefficients(&mut self, drive: f32, width: f32) {
+    self.m_s = 0.5 + 1.5 * (1. - width * 0.5);
+    self.one_over_a = (0.5 + 5.5 * drive) / self.m_s;
+    // the reversible part of the magnetisation never drops below 70%, so quiet echoes don't get gated
+    self.c = (1. - width * 0.5).sqrt();
+    // the Langevin function starts with a slope of 1/3, so this keeps quiet signals at unity gain
+    self.makeup_gain = 3. / (self.m_s * self.one_over_a * self.c);
+    self.prev_drive = drive;
+    self.prev_width = width;
+  }
+
+  /// Trapezoidal derivative of the magnetic field.
+  fn differentiate(&self, h: f32) -> f32 {
+    2. / self.t * (h - self.h_n1) - self.h_d_n1
+  }
+
+  fn solve(&self, h: f32, h_d: f32) -> f32 {
+    let k1 = self.t * self.get_dm_dt(self.m_n1, self.h_n1, self.h_d_n1);
+    let k2 = self.t
+      * self.get_dm_dt(
+        self.m_n1 + k1 * 0.5,
+        (h + self.h_n1) * 0.5,
+        (h_d + self.h_d_n1) * 0.5,
+      );
+    self.m_n1 + k2
+  }
+
+  fn get_dm_dt(&self, m: f32, h: f32, h_d: f32) -> f32 {
+    let q = (h + m * ALPHA) * self.one_over_a;
+    let (langevin, langevin_d) = Self::langevin(q);
+
+    let m_diff = self.m_s * langevin - m;
+    let delta: f32 = if h_d >= 0. { 1. } else { -1. };
+    let delta_m = if delta == m_diff.signum() { 1. } else { 0. };
+
+    let one_minus_c = 1. - self.c;
+    let m_s_over_a = self.m_s * self.one_over_a;
+    let f1 = one_minus_c * delta_m * m_diff / (one_minus_c * delta * COERCIVITY - ALPHA * m_diff);
+    let f2 = self.c * m_s_over_a * langevin_d;
+    let f3 = 1. - self.c * m_s_over_a * ALPHA * langevin_d;
+
+    h_d * (f1 + f2) / f3
+  }
+
+  /// Returns the Langevin function and its derivative.
+  fn langevin(q: f32) -> (f32, f32) {
+    if q.abs() < 0.001 {
+      (q / 3., 1. / 3.)
+    } else {
+      let coth = q.tanh().recip();
+      let q_recip = q.recip();
+      (coth - q_recip, q_recip * q_recip - coth * coth + 1.)
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::Hysteresis;
+  use std::f32::consts::TAU;
+
+  fn process_sine(hysteresis: &mut Hysteresis, amplitude: f32, drive: f32) -> Vec<(f32, f32)> {
+    (0..4800)
+      .map(|i| {
+        let input = (i as f32 / 48000. * 100. * TAU).sin() * amplitude;
+        (input, hysteresis.process(input, drive, 0.5))
+      })
+      .collect()
+  }
+
+  #[test]
+  fn should_stay_bounded_at_high_drive() {
+    let mut hysteresis = Hysteresis::new(48000.);
+    let output = process_sine(&mut hysteresis, 10., 1.);
+    assert!(output
+      .iter()
+      .all(|(_, output)| output.is_finite() && output.abs() <= 1.));
+  }
+
+  #[test]
+  fn should_have_memory() {
+    let mut hysteresis = Hysteresis::new(48000.);
+    let output = process_sine(&mut hysteresis, 1., 0.5);
+
+    // the last periods of the sine, on the way up and on the way down through zero input
+    let last_periods = &output[3840..];
+    let rising = last_periods
+      .windows(2)
+      .find(|pair| pair[0].0 < 0. && pair[1].0 >= 0.)
+      .map(|pair| pair[1].1)
+      .unwrap();
+    let falling = last_periods
+      .windows(2)
+      .find(|pair| pair[0].0 > 0. && pair[1].0 <= 0.)
+      .map(|pair| pair[1].1)
+      .unwrap();
+    assert!(rising < 0.);
+    assert!(falling > 0.);
+  }
+}
@@ -1,9 +1,10 @@
 use std::f32::consts::PI;
 
-#[allow(dead_code)]
-#[derive(Clone, Copy)]
+/// How the delay line is read between samples. The higher order interpolations keep more of the high end at fractional delay times.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
 pub enum Interpolation {
   Step,
+  #[default]
   Linear,
   Cosine,
   Cubic,
@@ -39,22 +40,42 @@ impl DelayLine {
     }
   }
 
+  /// The shortest time in milliseconds that's read with the given interpolation. Shorter times are clamped to it.
+  pub fn min_time(&self, interp: Interpolation) -> f32 {
+    Self::get_min_delay(interp) * 1000. / self.sample_rate
+  }
+
+  /// The buffer isn't resized, so the maximum delay time shrinks when the sample rate goes up.
+  pub fn set_sample_rate(&mut self, sample_rate: f32) {
+    self.sample_rate = sample_rate;
+  }
+
+  pub fn reset(&mut self) {
+    self.buffer.fill(0.);
+    self.write_pointer = 0;
+  }
+
   pub fn write(&mut self, value: f32) {
     self.buffer[self.write_pointer] = value;
     self.write_pointer = self.write_pointer + 1 & self.wrap;
   }
 
   fn step_interp(&self, time: f32) -> f32 {
-    let read_pointer =
-      (self.write_pointer + self.buffer.len()) as f32 - (self.mstosamps(time) - 0.5).max(1.);
+    let read_pointer = (self.write_pointer + self.buffer.len()) as f32
+      - (self
+        .mstosamps(time)
+        .max(Self::get_min_delay(Interpolation::Step))
+        - 0.5);
     let index = read_pointer.trunc() as usize;
 
     self.buffer[index & self.wrap]
   }
 
   fn linear_interp(&self, time: f32) -> f32 {
-    let read_pointer =
-      (self.write_pointer + self.buffer.len()) as f32 - self.mstosamps(time).max(1.);
+    let read_pointer = (self.write_pointer + self.buffer.len()) as f32
+      - self
+        .mstosamps(time)
+        .max(Self::get_min_delay(Interpolation::Linear));
     let rounded_read_pointer = read_pointer.trunc();
     let mix = read_pointer - rounded_read_pointer;
     let index = rounded_read_pointer as usize;
@@ -65,8 +86,10 @@ impl DelayLine {
   }
 
   fn cosine_interp(&self, time: f32) -> f32 {
-    let read_pointer =
-      (self.write_pointer + self.buffer.len()) as f32 - self.mstosamps(time).max(1.);
+    let read_pointer = (self.write_pointer + self.buffer.len()) as f32
+      - self
+        .mstosamps(time)
+        .max(Self::get_min_delay(Interpolation::Cosine));
     let rounded_read_pointer = read_pointer.trunc();
     let mix = read_pointer - rounded_read_pointer;
     let index = rounded_read_pointer as usize;
@@ -78,8 +101,10 @@ impl DelayLine {
   }
 
   fn cubic_interp(&self, time: f32) -> f32 {
-    let read_pointer =
-      (self.write_pointer + self.buffer.len()) as f32 - self.mstosamps(time).max(2.);
+    let read_pointer = (self.write_pointer + self.buffer.len()) as f32
+      - self
+        .mstosamps(time)
+        .max(Self::get_min_delay(Interpolation::Cubic));
     let rounded_read_pointer = read_pointer.trunc();
     let mix = read_pointer - rounded_read_pointer;
     let index = rounded_read_pointer as usize;
@@ -102,8 +127,10 @@ impl DelayLine {
   }
 
   fn spline_interp(&self, time: f32) -> f32 {
-    let read_pointer =
-      (self.write_pointer + self.buffer.len()) as f32 - self.mstosamps(time).max(2.);
+    let read_pointer = (self.write_pointer + self.buffer.len()) as f32
+      - self
+        .mstosamps(time)
+        .max(Self::get_min_delay(Interpolation::Spline));
     let rounded_read_pointer = read_pointer.trunc();
     let mix = read_pointer - rounded_read_pointer;
     let index = rounded_read_pointer as usize;
@@ -120,7 +147,92 @@ impl DelayLine {
     ((c3 * mix + c2) * mix + c1) * mix + c0
   }
 
+  /// The shortest delay in samples, so every sample the interpolation needs has been written before it's read.
+  fn get_min_delay(interp: Interpolation) -> f32 {
+    match interp {
+      Interpolation::Step => 1.5,
+      Interpolation::Linear | Interpolation::Cosine => 1.,
+      Interpolation::Cubic | Interpolation::Spline => 2.,
+    }
+  }
+
   fn mstosamps(&self, time: f32) -> f32 {
     time * 0.001 * self.sample_rate
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::{DelayLine, Interpolation};
+  use std::f32::consts::TAU;
+
+  const SAMPLE_RATE: f32 = 1000.;
+
+  /// Returns the gain of a sine at the given frequency, read back half a sample off the grid.
+  fn get_gain(frequency: f32, interpolation: Interpolation) -> f32 {
+    let mut delay_line = DelayLine::new(64, SAMPLE_RATE);
+    let (mut real, mut imaginary) = (0., 0.);
+
+    for i in 0..2000 {
+      let phase = i as f32 / SAMPLE_RATE * frequency * TAU;
+      delay_line.write(phase.sin());
+      let output = delay_line.read(10.5, interpolation);
+      if i >= 1000 {
+        real += output * phase.sin();
+        imaginary += output * phase.cos();
+      }
+    }
+
+    (real * real + imaginary * imaginary).sqrt() * 2. / 1000.
+  }
+
+  #[test]
+  fn should_pass_low_frequencies_at_unity_gain() {
+    [
+      Interpolation::Step,
+      Interpolation::Linear,
+      Interpolation::Cosine,
+      Interpolation::Cubic,
+      Interpolation::Spline,
+    ]
+    .into_iter()
+    .for_each(|interpolation| {
+      let gain = get_gain(10., interpolation);
+      assert!((gain - 1.).abs() < 0.01, "{:?}: {}", interpolation, gain);
+    });
+  }
+
+  #[test]
+  fn should_clamp_times_below_the_min_time() {
+    let mut delay_line = DelayLine::new(64, SAMPLE_RATE);
+    (0..64).for_each(|i| delay_line.write(i as f32));
+
+    [
+      Interpolation::Step,
+      Interpolation::Linear,
+      Interpolation::Cosine,
+      Interpolation::Cubic,
+      Interpolation::Spline,
+    ]
+    .into_iter()
+    .for_each(|interpolation| {
+      let min_time = delay_line.min_time(interpolation);
+      let output = delay_line.read(min_time, interpolation);
+      assert_eq!(delay_line.read(0., interpolation), output);
+      assert!(delay_line.read(min_time + 1., interpolation) < output);
+    });
+  }
+
+  #[test]
+  fn should_keep_more_high_end_with_higher_order_interpolation() {
+    let linear = get_gain(250., Interpolation::Linear);
+    let cosine = get_gain(250., Interpolation::Cosine);
+    let cubic = get_gain(250., Interpolation::Cubic);
+    let spline = get_gain(250., Interpolation::Spline);
+
+    assert!((linear - 0.5f32.sqrt()).abs() < 0.01);
+    assert!((cosine - linear).abs() < 0.01);
+    assert!(cubic > 0.85 && cubic < 1.);
+    assert!(spline > 0.85 && spline < 1.);
+  }
+}
@@ -2,6 +2,7 @@ use std::f32::consts::{FRAC_PI_2, PI};
 
 pub trait FloatExt {
   fn dbtoa(self) -> Self;
+  fn atodb(self) -> Self;
   fn scale(self, in_low: Self, in_high: Self, out_low: Self, out_high: Self) -> Self;
   fn mix(self, right: f32, factor: f32) -> Self;
   fn fast_atan1(self) -> Self;
@@ -16,6 +17,7 @@ pub trait FloatExt {
   fn fast_pow(self, exponent: Self) -> Self;
   fn fast_exp(self) -> Self;
   fn mstosamps(self, sample_rate: Self) -> Self;
+  fn sampstoms(self, sample_rate: Self) -> Self;
 }
 
 impl FloatExt for f32 {
@@ -24,6 +26,11 @@ impl FloatExt for f32 {
     (10_f32).powf(self * 0.05)
   }
 
+  /// Converts a linear amplitude value to decibels
+  fn atodb(self) -> Self {
+    20. * self.log10()
+  }
+
   fn scale(self, in_low: Self, in_high: Self, out_low: Self, out_high: Self) -> Self {
     let in_scale = 1. / (in_high - in_low);
     let out_range = out_high - out_low;
@@ -132,6 +139,11 @@ impl FloatExt for f32 {
   fn mstosamps(self, sample_rate: Self) -> Self {
     self * 0.001 * sample_rate
   }
+
+  /// Convert samples to milliseconds based on the samplerate.
+  fn sampstoms(self, sample_rate: Self) -> Self {
+    self * 1000. / sample_rate
+  }
 }
 
 #[cfg(test)]
@@ -155,6 +167,13 @@ mod tests {
     assert_eq!((-12f32).dbtoa(), 0.25118864);
   }
 
+  #[test]
+  fn atodb() {
+    assert_approximately_eq((0.5f32).atodb(), -6.0206, 4);
+    assert_eq!((1f32).atodb(), 0.);
+    assert_eq!((0f32).atodb(), f32::NEG_INFINITY);
+  }
+
   #[test]
   fn scale() {
     assert_eq!((1f32).scale(1., 500., -6., -15.), -6.);
@@ -1,4 +1,10 @@
-use crate::shared::float_ext::FloatExt;
+use crate::{
+  shared::{
+    float_ext::FloatExt,
+    stereo_block::{load, StereoBlock},
+  },
+  MAX_BLOCK_SIZE,
+};
 use std::f32::consts::FRAC_PI_2;
 
 pub struct Mix {
@@ -17,22 +23,53 @@ impl Mix {
   }
 
   pub fn process(&mut self, dry: (f32, f32), wet: (f32, f32), mix: f32) -> (f32, f32) {
+    self.set_mix(mix);
+    (
+      dry.0 * self.dry_gain + wet.0 * self.wet_gain,
+      dry.1 * self.dry_gain + wet.1 * self.wet_gain,
+    )
+  }
+
+  /// Mixes the wet block into the dry block. The gains are only recalculated when the mix changes, the mixing is done eight samples at a time.
+  pub fn process_block(
+    &mut self,
+    dry: &mut StereoBlock,
+    wet: &StereoBlock,
+    mix: &[f32; MAX_BLOCK_SIZE],
+    block_size: usize,
+  ) {
+    let mut dry_gain = [0.; MAX_BLOCK_SIZE];
+    let mut wet_gain = [0.; MAX_BLOCK_SIZE];
+    for i in 0..block_size {
+      self.set_mix(mix[i]);
+      dry_gain[i] = self.dry_gain;
+      wet_gain[i] = self.wet_gain;
+    }
+
+    dry.map(block_size, |i, left, right| {
+      let dry_gain = load(&dry_gain, i);
+      let wet_gain = load(&wet_gain, i);
+      (
+        left * dry_gain + load(&wet.left, i) * wet_gain,
+        right * dry_gain + load(&wet.right, i) * wet_gain,
+      )
+    });
+  }
+
+  fn set_mix(&mut self, mix: f32) {
     if mix != self.mix {
       let factor = mix * FRAC_PI_2;
       self.mix = mix;
       self.dry_gain = factor.fast_cos();
       self.wet_gain = factor.fast_sin();
     }
-    (
-      dry.0 * self.dry_gain + wet.0 * self.wet_gain,
-      dry.1 * self.dry_gain + wet.1 * self.wet_gain,
-    )
   }
 }
 
 #[cfg(test)]
 mod tests {
   use super::Mix;
+  use crate::{shared::stereo_block::StereoBlock, MAX_BLOCK_SIZE};
 
   fn assert_approximately_eq(left: f32, right: f32, digits: usize) {
     let tol = 10f32.powi(-(digits as i32));
@@ -56,4 +93,19 @@ mod tests {
     assert_approximately_eq(third.0, 1., 3);
     assert_approximately_eq(third.1, 1., 3);
   }
+
+  #[test]
+  fn should_mix_a_block_like_single_samples() {
+    let mut mix = Mix::new();
+    let mut reference = Mix::new();
+    let mix_values: [f32; MAX_BLOCK_SIZE] = std::array::from_fn(|i| i as f32 / 40.);
+    let mut dry = StereoBlock::from_slices(&[1.; 27], &[0.5; 27]);
+    let wet = StereoBlock::from_slices(&[-1.; 27], &[0.25; 27]);
+    mix.process_block(&mut dry, &wet, &mix_values, 27);
+
+    for (i, mix_value) in mix_values.into_iter().enumerate().take(27) {
+      let (left, right) = reference.process((1., 0.5), (-1., 0.25), mix_value);
+      assert_eq!(dry.get(i).to_array(), [left, right]);
+    }
+  }
 }
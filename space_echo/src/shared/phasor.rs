@@ -1,5 +1,7 @@
 pub struct Phasor {
   x: f32,
+  freq: f32,
+  sample_period: f32,
   step_size: f32,
 }
 
@@ -7,10 +9,26 @@ impl Phasor {
   pub fn new(sample_rate: f32, freq: f32) -> Self {
     Self {
       x: 0.,
+      freq,
+      sample_period: sample_rate.recip(),
       step_size: sample_rate.recip() * freq,
     }
   }
 
+  pub fn set_sample_rate(&mut self, sample_rate: f32) {
+    self.sample_period = sample_rate.recip();
+    self.step_size = self.sample_period * self.freq;
+  }
+
+  pub fn set_freq(&mut self, freq: f32) {
+    self.freq = freq;
+    self.step_size = self.sample_period * freq;
+  }
+
+  pub fn reset(&mut self) {
+    self.x = 0.;
+  }
+
   pub fn process(&mut self) -> f32 {
     self.x = self.wrap(self.x + self.step_size);
     self.x
@@ -1,29 +1,40 @@
 use crate::shared::{delta::Delta, float_ext::FloatExt};
+use fastrand::Rng;
 use std::f32::consts::PI;
 
-#[derive(Clone, Copy)]
+#[derive(Clone)]
 pub struct RandomOscillator {
+  rng: Rng,
   origin: f32,
   target: f32,
   delta: Delta,
 }
 
 impl RandomOscillator {
-  pub fn new() -> Self {
+  pub fn new(seed: u64) -> Self {
     Self {
+      rng: Rng::with_seed(seed),
       origin: 0.,
       target: 0.,
       delta: Delta::new(),
     }
   }
 
+  /// Restarts the random sequence from the seed, so the oscillator repeats the same movement.
+  pub fn seed(&mut self, seed: u64) {
+    self.rng.seed(seed);
+    self.origin = 0.;
+    self.target = 0.;
+    self.delta = Delta::new();
+  }
+
   pub fn process(&mut self, phase: f32, probability: f32) -> f32 {
     let trigger = self.delta.process(phase) < 0.;
 
     if trigger {
       self.origin = self.target;
-      self.target = if probability == 1. || fastrand::f32() <= probability {
-        fastrand::f32()
+      self.target = if probability == 1. || self.rng.f32() <= probability {
+        self.rng.f32()
       } else {
         0.
       };
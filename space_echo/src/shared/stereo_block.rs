@@ -0,0 +1,93 @@
+use {
+  crate::MAX_BLOCK_SIZE,
+  std::simd::{f32x2, f32x8, num::SimdFloat},
+};
+
+/// The number of samples that are processed at once by the block-wide stages.
+pub const LANES: usize = 8;
+
+/// Loads the eight samples of a block that start at the index.
+pub fn load(block: &[f32; MAX_BLOCK_SIZE], index: usize) -> f32x8 {
+  f32x8::from_slice(&block[index..])
+}
+
+/// The left and right channel of a block, padded to the maximum block size so the block-wide stages can always process eight samples.
+#[derive(Clone, Copy)]
+pub struct StereoBlock {
+  pub left: [f32; MAX_BLOCK_SIZE],
+  pub right: [f32; MAX_BLOCK_SIZE],
+}
+
+impl StereoBlock {
+  pub fn new() -> Self {
+    Self {
+      left: [0.; MAX_BLOCK_SIZE],
+      right: [0.; MAX_BLOCK_SIZE],
+    }
+  }
+
+  pub fn from_slices(left: &[f32], right: &[f32]) -> Self {
+    let mut block = Self::new();
+    block.left[..left.len()].copy_from_slice(left);
+    block.right[..right.len()].copy_from_slice(right);
+    block
+  }
+
+  pub fn copy_to_slices(&self, left: &mut [f32], right: &mut [f32]) {
+    left.copy_from_slice(&self.left[..left.len()]);
+    right.copy_from_slice(&self.right[..right.len()]);
+  }
+
+  pub fn get(&self, index: usize) -> f32x2 {
+    f32x2::from_array([self.left[index], self.right[index]])
+  }
+
+  pub fn set(&mut self, index: usize, value: f32x2) {
+    self.left[index] = value[0];
+    self.right[index] = value[1];
+  }
+
+  /// Replaces the first samples of the block, eight at a time. The callback gets the index of the first sample and the left and right channel.
+  pub fn map(
+    &mut self,
+    block_size: usize,
+    mut f: impl FnMut(usize, f32x8, f32x8) -> (f32x8, f32x8),
+  ) {
+    for index in (0..block_size).step_by(LANES) {
+      let (left, right) = f(index, load(&self.left, index), load(&self.right, index));
+      left.copy_to_slice(&mut self.left[index..index + LANES]);
+      right.copy_to_slice(&mut self.right[index..index + LANES]);
+    }
+  }
+
+  /// The highest absolute value of the left and right channel.
+  pub fn peak(&self, block_size: usize) -> (f32, f32) {
+    (0..block_size)
+      .step_by(LANES)
+      .fold((0_f32, 0_f32), |(left, right), index| {
+        (
+          left.max(load(&self.left, index).abs().reduce_max()),
+          right.max(load(&self.right, index).abs().reduce_max()),
+        )
+      })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::StereoBlock;
+  use std::simd::f32x8;
+
+  #[test]
+  fn should_only_process_the_samples_of_the_block() {
+    let mut block = StereoBlock::from_slices(&[1.; 20], &[-2.; 20]);
+    block.map(20, |_, left, right| (left * f32x8::splat(0.5), right));
+
+    let mut left = [0.; 20];
+    let mut right = [0.; 20];
+    block.copy_to_slices(&mut left, &mut right);
+    assert_eq!(left, [0.5; 20]);
+    assert_eq!(right, [-2.; 20]);
+    assert_eq!(block.peak(20), (0.5, 2.));
+  }
+}
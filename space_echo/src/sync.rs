@@ -0,0 +1,176 @@
+use std::{error::Error, fmt, str::FromStr};
+
+/// The tempo that's used when the host doesn't provide one.
+pub const DEFAULT_BPM: f32 = 120.;
+
+/// A note length relative to a whole note, like 4 for a quarter note.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum NoteDivision {
+  Straight(u32),
+  Dotted(u32),
+  Triplet(u32),
+}
+
+/// The selectable note divisions, from short to long. The position in this list is the division index that the plugins use.
+pub const NOTE_DIVISIONS: [NoteDivision; 16] = [
+  NoteDivision::Straight(32),
+  NoteDivision::Triplet(16),
+  NoteDivision::Dotted(32),
+  NoteDivision::Straight(16),
+  NoteDivision::Triplet(8),
+  NoteDivision::Dotted(16),
+  NoteDivision::Straight(8),
+  NoteDivision::Triplet(4),
+  NoteDivision::Dotted(8),
+  NoteDivision::Straight(4),
+  NoteDivision::Triplet(2),
+  NoteDivision::Dotted(4),
+  NoteDivision::Straight(2),
+  NoteDivision::Triplet(1),
+  NoteDivision::Dotted(2),
+  NoteDivision::Straight(1),
+];
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum SyncError {
+  IndexOutOfRange(i32),
+  UnknownName(String),
+}
+
+impl fmt::Display for SyncError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      SyncError::IndexOutOfRange(index) => write!(
+        f,
+        "note division index {} is out of range 0..{}",
+        index,
+        NOTE_DIVISIONS.len()
+      ),
+      SyncError::UnknownName(name) => write!(f, "unknown note division \"{}\"", name),
+    }
+  }
+}
+
+impl Error for SyncError {}
+
+impl NoteDivision {
+  /// Returns the length in quarter note beats.
+  pub fn beats(&self) -> f32 {
+    match *self {
+      NoteDivision::Straight(note) => 4. / note as f32,
+      NoteDivision::Dotted(note) => 6. / note as f32,
+      NoteDivision::Triplet(note) => 8. / 3. / note as f32,
+    }
+  }
+
+  /// Returns the length in milliseconds at the given tempo.
+  pub fn to_ms(&self, bpm: f32) -> f32 {
+    60000. / bpm * self.beats()
+  }
+
+  /// Returns the position in [`NOTE_DIVISIONS`], if it's one of the selectable divisions.
+  pub fn index(&self) -> Option<i32> {
+    NOTE_DIVISIONS
+      .iter()
+      .position(|division| division == self)
+      .map(|index| index as i32)
+  }
+}
+
+impl TryFrom<i32> for NoteDivision {
+  type Error = SyncError;
+
+  fn try_from(index: i32) -> Result<Self, Self::Error> {
+    usize::try_from(index)
+      .ok()
+      .and_then(|index| NOTE_DIVISIONS.get(index))
+      .copied()
+      .ok_or(SyncError::IndexOutOfRange(index))
+  }
+}
+
+impl fmt::Display for NoteDivision {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let (note, suffix) = match *self {
+      NoteDivision::Straight(note) => (note, ""),
+      NoteDivision::Dotted(note) => (note, "."),
+      NoteDivision::Triplet(note) => (note, "T"),
+    };
+
+    match note {
+      1 => write!(f, "1{}", suffix),
+      _ => write!(f, "1/{}{}", note, suffix),
+    }
+  }
+}
+
+impl FromStr for NoteDivision {
+  type Err = SyncError;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let s = s.trim();
+    let division: fn(u32) -> NoteDivision = if s.ends_with('.') {
+      NoteDivision::Dotted
+    } else if s.ends_with(['T', 't']) {
+      NoteDivision::Triplet
+    } else {
+      NoteDivision::Straight
+    };
+    let note = s.strip_suffix(['.', 'T', 't']).unwrap_or(s);
+    let note = match note {
+      "1" => Some(1),
+      _ => note.strip_prefix("1/").and_then(|note| note.parse().ok()),
+    };
+
+    note
+      .filter(|note| *note > 0)
+      .map(division)
+      .ok_or_else(|| SyncError::UnknownName(s.to_string()))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{NoteDivision, SyncError, NOTE_DIVISIONS};
+
+  #[test]
+  fn should_convert_divisions_to_milliseconds() {
+    assert_eq!(NoteDivision::Straight(4).to_ms(120.), 500.);
+    assert_eq!(NoteDivision::Dotted(8).to_ms(120.), 375.);
+    assert_eq!(NoteDivision::Straight(1).to_ms(60.), 4000.);
+    assert!((NoteDivision::Triplet(4).to_ms(120.) - 333.333).abs() < 0.001);
+  }
+
+  #[test]
+  fn should_order_divisions_from_short_to_long() {
+    assert!(NOTE_DIVISIONS
+      .windows(2)
+      .all(|divisions| divisions[0].beats() < divisions[1].beats()));
+  }
+
+  #[test]
+  fn should_convert_indices() {
+    assert_eq!(NoteDivision::try_from(9), Ok(NoteDivision::Straight(4)));
+    assert_eq!(NoteDivision::Triplet(2).index(), Some(10));
+    assert_eq!(
+      NoteDivision::try_from(16),
+      Err(SyncError::IndexOutOfRange(16))
+    );
+    assert_eq!(
+      NoteDivision::try_from(-1),
+      Err(SyncError::IndexOutOfRange(-1))
+    );
+  }
+
+  #[test]
+  fn should_format_and_parse_names() {
+    NOTE_DIVISIONS.iter().for_each(|division| {
+      assert_eq!(division.to_string().parse(), Ok(*division));
+    });
+    assert_eq!(NoteDivision::Dotted(2).to_string(), "1/2.");
+    assert_eq!(NoteDivision::Triplet(1).to_string(), "1T");
+    assert_eq!("1/8t".parse(), Ok(NoteDivision::Triplet(8)));
+    assert!("1/0".parse::<NoteDivision>().is_err());
+    assert!("quarter".parse::<NoteDivision>().is_err());
+  }
+}
@@ -0,0 +1,220 @@
+use {
+  crate::{
+    params::{HeadMode, TimeMode},
+    shared::delay_line::{DelayLine, Interpolation},
+    variable_delay_read::{ReverseDelayRead, VariableDelayRead},
+  },
+  std::{array, simd::f32x2},
+};
+
+/// The distance of each playback head to the record head, relative to the delay time.
+pub const HEAD_RATIOS: [f32; 3] = [1. / 3., 2. / 3., 1.];
+
+/// Returns which playback heads are engaged and whether the reverb is engaged.
+pub fn get_head_mode(head_mode: HeadMode) -> ([bool; 3], bool) {
+  match head_mode {
+    HeadMode::SingleHead => ([false, false, true], true),
+    HeadMode::Mode1 => ([true, false, false], false),
+    HeadMode::Mode2 => ([false, true, false], false),
+    HeadMode::Mode3 => ([false, false, true], false),
+    HeadMode::Mode4 => ([false, true, true], false),
+    HeadMode::Mode5 => ([true, false, false], true),
+    HeadMode::Mode6 => ([false, true, false], true),
+    HeadMode::Mode7 => ([false, false, true], true),
+    HeadMode::Mode8 => ([true, true, false], true),
+    HeadMode::Mode9 => ([false, true, true], true),
+    HeadMode::Mode10 => ([true, false, true], true),
+    HeadMode::Mode11 => ([true, true, true], true),
+    HeadMode::Mode12 => ([false, false, false], true),
+  }
+}
+
+pub struct TapeHeads {
+  variable_delay_read_left: [VariableDelayRead; 3],
+  variable_delay_read_right: [VariableDelayRead; 3],
+  reverse_delay_read_left: [ReverseDelayRead; 3],
+  reverse_delay_read_right: [ReverseDelayRead; 3],
+}
+
+impl TapeHeads {
+  pub fn new(sample_rate: f32) -> Self {
+    Self {
+      variable_delay_read_left: array::from_fn(|_| VariableDelayRead::new(sample_rate)),
+      variable_delay_read_right: array::from_fn(|_| VariableDelayRead::new(sample_rate)),
+      reverse_delay_read_left: array::from_fn(|_| ReverseDelayRead::new(sample_rate)),
+      reverse_delay_read_right: array::from_fn(|_| ReverseDelayRead::new(sample_rate)),
+    }
+  }
+
+  pub fn set_sample_rate(&mut self, sample_rate: f32) {
+    self
+      .variable_delay_read_left
+      .iter_mut()
+      .chain(self.variable_delay_read_right.iter_mut())
+      .for_each(|variable_delay_read| variable_delay_read.set_sample_rate(sample_rate));
+    self
+      .reverse_delay_read_left
+      .iter_mut()
+      .chain(self.reverse_delay_read_right.iter_mut())
+      .for_each(|reverse_delay_read| reverse_delay_read.set_sample_rate(sample_rate));
+  }
+
+  pub fn reset(&mut self) {
+    self
+      .variable_delay_read_left
+      .iter_mut()
+      .chain(self.variable_delay_read_right.iter_mut())
+      .for_each(|variable_delay_read| variable_delay_read.reset());
+    self
+      .reverse_delay_read_left
+      .iter_mut()
+      .chain(self.reverse_delay_read_right.iter_mut())
+      .for_each(|reverse_delay_read| reverse_delay_read.reset());
+  }
+
+  /// Reads every engaged head and scales it by its gain. Heads with a gain of zero are not read. <br />
+  /// The added time can be negative to compensate latency, but each head is read at least at the shortest time of the delay line.
+  /// Heads that would read earlier than that play back late.
+  pub fn read(
+    &mut self,
+    delay_lines: (&DelayLine, &DelayLine),
+    time: (f32, f32),
+    time_mode: TimeMode,
+    interpolation: Interpolation,
+    wow_and_flutter_time: f32,
+    head_gains: [f32; 3],
+  ) -> [f32x2; 3] {
+    let min_time = delay_lines.0.min_time(interpolation);
+    array::from_fn(|i| {
+      let gain = head_gains[i];
+      if gain == 0. {
+        return f32x2::splat(0.);
+      }
+
+      let time_left = time.0 * HEAD_RATIOS[i];
+      let time_right = time.1 * HEAD_RATIOS[i];
+      let added_time_left = wow_and_flutter_time.max(min_time - time_left);
+      let added_time_right = wow_and_flutter_time.max(min_time - time_right);
+
+      let head_output = match time_mode {
+        TimeMode::Repitch => f32x2::from_array([
+          delay_lines
+            .0
+            .read(time_left + added_time_left, interpolation),
+          delay_lines
+            .1
+            .read(time_right + added_time_right, interpolation),
+        ]),
+        TimeMode::Fade => f32x2::from_array([
+          self.variable_delay_read_left[i].read(
+            delay_lines.0,
+            time_left,
+            added_time_left,
+            interpolation,
+          ),
+          self.variable_delay_read_right[i].read(
+            delay_lines.1,
+            time_right,
+            added_time_right,
+            interpolation,
+          ),
+        ]),
+        TimeMode::Reverse => f32x2::from_array([
+          self.reverse_delay_read_left[i].read(
+            delay_lines.0,
+            time_left,
+            added_time_left,
+            interpolation,
+          ),
+          self.reverse_delay_read_right[i].read(
+            delay_lines.1,
+            time_right,
+            added_time_right,
+            interpolation,
+          ),
+        ]),
+      };
+
+      head_output * f32x2::splat(gain)
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{get_head_mode, TapeHeads};
+  use crate::{
+    params::{HeadMode, TimeMode},
+    shared::delay_line::{DelayLine, Interpolation},
+  };
+
+  #[test]
+  fn should_play_back_each_head_at_its_ratio_of_the_delay_time() {
+    let mut delay_line = DelayLine::new(64, 1000.);
+    let mut tape_heads = TapeHeads::new(1000.);
+
+    delay_line.write(1.);
+    let outputs: Vec<[f32; 3]> = (1..=30)
+      .map(|_| {
+        let heads = tape_heads.read(
+          (&delay_line, &delay_line),
+          (30., 30.),
+          TimeMode::Repitch,
+          Interpolation::Linear,
+          0.,
+          [1., 1., 1.],
+        );
+        delay_line.write(0.);
+        heads.map(|head| head[0])
+      })
+      .collect();
+
+    let impulse_positions: Vec<Option<usize>> = (0..3)
+      .map(|head| outputs.iter().position(|output| output[head] == 1.))
+      .collect();
+    assert_eq!(impulse_positions, vec![Some(9), Some(19), Some(29)]);
+  }
+
+  #[test]
+  fn should_not_read_muted_heads() {
+    let mut delay_line = DelayLine::new(64, 1000.);
+    let mut tape_heads = TapeHeads::new(1000.);
+    (0..64).for_each(|_| delay_line.write(1.));
+
+    let heads = tape_heads.read(
+      (&delay_line, &delay_line),
+      (30., 30.),
+      TimeMode::Fade,
+      Interpolation::Linear,
+      0.,
+      [0., 0.5, 1.],
+    );
+    assert_eq!(
+      heads.map(|head| head.to_array()),
+      [[0., 0.], [0.5, 0.5], [1., 1.]]
+    );
+  }
+
+  #[test]
+  fn should_map_the_mode_selector() {
+    assert_eq!(
+      get_head_mode(HeadMode::SingleHead),
+      ([false, false, true], true)
+    );
+    assert_eq!(get_head_mode(HeadMode::Mode4), ([false, true, true], false));
+    assert_eq!(get_head_mode(HeadMode::Mode11), ([true, true, true], true));
+    assert_eq!(
+      get_head_mode(HeadMode::Mode12),
+      ([false, false, false], true)
+    );
+  }
+
+  #[test]
+  fn should_clamp_the_selector_position() {
+    assert_eq!(HeadMode::from(0), HeadMode::SingleHead);
+    assert_eq!(HeadMode::from(4), HeadMode::Mode4);
+    assert_eq!(HeadMode::from(12), HeadMode::Mode12);
+    assert_eq!(HeadMode::from(-1), HeadMode::SingleHead);
+    assert_eq!(HeadMode::from(13), HeadMode::Mode12);
+  }
+}
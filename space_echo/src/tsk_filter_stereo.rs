@@ -24,6 +24,21 @@ impl TSKFilterStereo {
     }
   }
 
+  pub fn set_sample_rate(&mut self, sample_rate: f32) {
+    self
+      .one_pole_filters
+      .iter_mut()
+      .for_each(|one_pole_filter| one_pole_filter.set_sample_rate(sample_rate));
+  }
+
+  pub fn reset(&mut self) {
+    self
+      .one_pole_filters
+      .iter_mut()
+      .for_each(|one_pole_filter| one_pole_filter.reset());
+    self.z = f32x2::splat(0.);
+  }
+
   pub fn process(
     &mut self,
     input: f32x2,
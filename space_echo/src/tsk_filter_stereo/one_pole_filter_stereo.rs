@@ -19,6 +19,16 @@ impl OnePoleFilterStereo {
     }
   }
 
+  pub fn set_sample_rate(&mut self, sample_rate: f32) {
+    self.t = sample_rate.recip() * -TAU;
+    // forces the coefficient to be recalculated on the next sample
+    self.prev_cutoff_freq = 0.;
+  }
+
+  pub fn reset(&mut self) {
+    self.z = f32x2::splat(0.);
+  }
+
   pub fn process(&mut self, input: f32x2, freq: f32, filter_type: FilterType) -> f32x2 {
     match filter_type {
       FilterType::Lowpass => self.apply_filter(input, freq),
@@ -1,7 +1,11 @@
 mod ramp;
-use super::{shared::float_ext::FloatExt, DelayLine, Interpolation};
-use ramp::Ramp;
+mod reverse_delay_read;
+use super::shared::{
+  delay_line::{DelayLine, Interpolation},
+  float_ext::FloatExt,
+};
 use std::f32::consts::FRAC_PI_2;
+pub use {ramp::Ramp, reverse_delay_read::ReverseDelayRead};
 
 pub struct VariableDelayRead {
   ramp: Ramp,
@@ -18,6 +22,16 @@ impl VariableDelayRead {
     }
   }
 
+  pub fn set_sample_rate(&mut self, sample_rate: f32) {
+    self.ramp.set_sample_rate(sample_rate);
+  }
+
+  /// Stops a running crossfade and jumps to the latest delay time.
+  pub fn reset(&mut self) {
+    self.ramp.reset();
+    self.previous_time = self.next_time;
+  }
+
   pub fn read(
     &mut self,
     delay_line: &DelayLine,
@@ -2,6 +2,7 @@ pub struct Ramp {
   x: f32,
   trigger: bool,
   is_active: bool,
+  freq: f32,
   step_size: f32,
 }
 
@@ -11,10 +12,21 @@ impl Ramp {
       x: 0.,
       trigger: false,
       is_active: false,
+      freq,
       step_size: sample_rate.recip() * freq,
     }
   }
 
+  pub fn set_sample_rate(&mut self, sample_rate: f32) {
+    self.step_size = sample_rate.recip() * self.freq;
+  }
+
+  pub fn reset(&mut self) {
+    self.x = 0.;
+    self.trigger = false;
+    self.is_active = false;
+  }
+
   pub fn start(&mut self) {
     self.trigger = true;
     self.is_active = true;
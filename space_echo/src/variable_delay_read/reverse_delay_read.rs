@@ -0,0 +1,97 @@
+use super::ramp::Ramp;
+use crate::shared::{
+  delay_line::{DelayLine, Interpolation},
+  float_ext::FloatExt,
+};
+use std::f32::consts::FRAC_PI_2;
+
+const FADE_TIME_IN_MS: f32 = 10.;
+
+/// Plays the delay line backwards in segments of half the delay time. <br />
+/// The read position moves back while the tape moves forward, so each segment sweeps the delay from zero up to the full delay time. <br />
+/// The next segment starts a fade time before the current one ends, so the two are crossfaded without reading past the delay time.
+pub struct ReverseDelayRead {
+  ramp: Ramp,
+  sample_period: f32,
+  elapsed: f32,
+  previous_elapsed: f32,
+}
+
+impl ReverseDelayRead {
+  pub fn new(sample_rate: f32) -> Self {
+    Self {
+      ramp: Ramp::new(sample_rate, 1000. / FADE_TIME_IN_MS),
+      sample_period: 1000. / sample_rate,
+      elapsed: 0.,
+      previous_elapsed: 0.,
+    }
+  }
+
+  pub fn set_sample_rate(&mut self, sample_rate: f32) {
+    self.ramp.set_sample_rate(sample_rate);
+    self.sample_period = 1000. / sample_rate;
+  }
+
+  /// Stops a running crossfade and starts a new segment.
+  pub fn reset(&mut self) {
+    self.ramp.reset();
+    self.elapsed = 0.;
+    self.previous_elapsed = 0.;
+  }
+
+  pub fn read(
+    &mut self,
+    delay_line: &DelayLine,
+    time: f32,
+    added_time: f32,
+    interp: Interpolation,
+  ) -> f32 {
+    if self.ramp.is_finished() && self.elapsed + FADE_TIME_IN_MS >= time * 0.5 {
+      self.previous_elapsed = self.elapsed;
+      self.elapsed = 0.;
+      self.ramp.start();
+    }
+
+    let next = delay_line.read(self.elapsed * 2. + added_time, interp);
+    self.elapsed += self.sample_period;
+
+    if self.ramp.is_finished() {
+      next
+    } else {
+      let ramp = self.ramp.process();
+      let window = (ramp * FRAC_PI_2).fast_cos();
+      let window = window * window;
+
+      // the previous segment can only overshoot the delay time when the delay time is shorter than the fade
+      let previous_time = (self.previous_elapsed * 2.).min(time);
+      let previous = delay_line.read(previous_time + added_time, interp);
+      self.previous_elapsed += self.sample_period;
+
+      next + (previous - next) * window
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::ReverseDelayRead;
+  use crate::shared::delay_line::{DelayLine, Interpolation};
+
+  #[test]
+  fn should_play_segments_backwards() {
+    let mut delay_line = DelayLine::new(256, 1000.);
+    let mut reverse_delay_read = ReverseDelayRead::new(1000.);
+
+    let outputs: Vec<f32> = (0..400)
+      .map(|i| {
+        delay_line.write(i as f32);
+        reverse_delay_read.read(&delay_line, 100., 0., Interpolation::Linear)
+      })
+      .collect();
+
+    // after the crossfade at the start of the segment that begins at sample 320
+    let segment = &outputs[331..360];
+    assert!(segment.windows(2).all(|pair| pair[1] < pair[0]));
+    assert!(outputs.iter().all(|output| *output >= 0. && *output < 400.));
+  }
+}
@@ -1,8 +1,9 @@
 use crate::shared::{phasor::Phasor, random_oscillator::RandomOscillator};
+use fastrand::Rng;
 
-const MAX_FLUTTER_TIME_IN_SECS: f32 = 2.;
-const MAX_WOW_TIME_IN_SECS: f32 = 15.;
-pub const MAX_WOW_AND_FLUTTER_TIME_IN_SECS: f32 = MAX_FLUTTER_TIME_IN_SECS + MAX_WOW_TIME_IN_SECS;
+const MAX_FLUTTER_TIME_IN_MS: f32 = 2.;
+const MAX_WOW_TIME_IN_MS: f32 = 15.;
+pub const MAX_WOW_AND_FLUTTER_TIME_IN_MS: f32 = MAX_FLUTTER_TIME_IN_MS + MAX_WOW_TIME_IN_MS;
 
 pub struct WowAndFlutter {
   wow_phasor: Phasor,
@@ -15,21 +16,49 @@ impl WowAndFlutter {
   pub fn new(sample_rate: f32) -> Self {
     Self {
       wow_phasor: Phasor::new(sample_rate, 2.1),
-      wow_oscillator: RandomOscillator::new(),
+      wow_oscillator: RandomOscillator::new(0),
       flutter_phasor: Phasor::new(sample_rate, 24.37891),
-      flutter_oscillator: RandomOscillator::new(),
+      flutter_oscillator: RandomOscillator::new(1),
     }
   }
 
+  /// Restarts the oscillators, with a random sequence for each that's derived from the seed.
+  pub fn seed(&mut self, seed: u64) {
+    let mut rng = Rng::with_seed(seed);
+    self.wow_phasor.reset();
+    self.wow_oscillator.seed(rng.u64(..));
+    self.flutter_phasor.reset();
+    self.flutter_oscillator.seed(rng.u64(..));
+  }
+
+  pub fn set_sample_rate(&mut self, sample_rate: f32) {
+    self.wow_phasor.set_sample_rate(sample_rate);
+    self.flutter_phasor.set_sample_rate(sample_rate);
+  }
+
   pub fn process(&mut self, flutter_gain: f32) -> f32 {
     let wow_oscillator = self.get_wow_oscillator();
     let flutter_oscillator = self.get_flutter_oscillator();
     wow_oscillator * flutter_gain * flutter_gain + flutter_oscillator * flutter_gain
   }
 
+  /// Writes the wow and flutter time for each sample of the block. The oscillators pause while the flutter gain is zero.
+  pub fn process_block(&mut self, flutter_gain: &[f32], output: &mut [f32]) {
+    output
+      .iter_mut()
+      .zip(flutter_gain)
+      .for_each(|(x, flutter_gain)| {
+        *x = if *flutter_gain > 0. {
+          self.process(*flutter_gain)
+        } else {
+          0.
+        }
+      });
+  }
+
   pub fn get_wow_oscillator(&mut self) -> f32 {
     let wow_oscillator_phase = self.wow_phasor.process();
-    self.wow_oscillator.process(wow_oscillator_phase, 0.4) * MAX_WOW_TIME_IN_SECS
+    self.wow_oscillator.process(wow_oscillator_phase, 0.4) * MAX_WOW_TIME_IN_MS
   }
 
   pub fn get_flutter_oscillator(&mut self) -> f32 {
@@ -37,6 +66,6 @@ impl WowAndFlutter {
     self
       .flutter_oscillator
       .process(flutter_oscillator_phase, 0.95)
-      * MAX_FLUTTER_TIME_IN_SECS
+      * MAX_FLUTTER_TIME_IN_MS
   }
 }